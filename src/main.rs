@@ -1,6 +1,11 @@
+#[path = "arithmetic/field.rs"]
 mod field;
+#[path = "sumchecks/mle.rs"]
 mod mle;
+#[path = "sumchecks/smcheck.rs"]
 mod smcheck;
+#[path = "sumchecks/ringswitch.rs"]
+mod ringswitch;
 
 // benchmark
 use std::time::Instant;
@@ -17,7 +22,8 @@ use ark_ff::Field;
 // MyLib
 use field::{Fq, Fq4, fq2fq4};
 use mle::{mle_from_vec_fq4, mle_from_table, mle_from_table_fq4};
-use smcheck::{fix_tau_eval_table, build_f_table, compute_a_eq_sum_i_prime_fq4, sumcheck_prove_from_table, sumcheck_prove_from_table_range, sumcheck_round_once, sumcheck_round_once_range};
+use ringswitch::{Subring, switch_rows, switched_claim, build_f_table_switched};
+use smcheck::{fix_tau_eval_table, build_f_table, compute_a_eq_sum_i_prime_fq4, sumcheck_prove_from_table, sumcheck_prove_from_table_range, sumcheck_verify_from_table};
 
 pub const N: usize = 64;
 pub const Q: u32 = 4294967197; // 231-227+1
@@ -118,7 +124,7 @@ pub fn build_f0_table_beta8<Fq: Field + From<u64>>(
 }
 
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut rng = OsRng;
     
     // init random mz = y (use 32 first)
@@ -263,31 +269,22 @@ fn main() {
     // start = Instant::now();
     
     // Verifier
-    let mut layer = f_table;
-    for (round, (&(c0,c1), &r)) in proof.g_coeffs.iter().zip(challenge.iter()).enumerate() {
-        // g_t(0) + g_t(1) = (c0) + (c0+c1) = 2*c0 + c1
-        let lhs_round_sum = layer.iter().copied().fold(Fq4::zero(), |acc,x| acc + x);
-        let rhs_round_sum = c0 + (c0 + c1);
-        if round == 0{
-            assert_eq!(lhs_round_sum, a, "init sum check failed");
-        }
-        assert_eq!(lhs_round_sum, rhs_round_sum, "round {} sum check failed", round);
-
-        // verifier_time += start.elapsed();
-        // start = Instant::now();
-
-        let (_, next) = sumcheck_round_once(&layer, r);
-
-        // prover_time3 += start.elapsed();
-        // start = Instant::now();
-
-        let g_at_r = c0 + c1 * r;
-        let next_sum = next.iter().copied().fold(Fq4::zero(), |acc,x| acc + x);
-        assert_eq!(g_at_r, next_sum, "round {} eval check failed", round);
-        layer = next;
-    }
-
-    assert_eq!(layer[0], proof.final_eval);
+    sumcheck_verify_from_table(f_table, a, &proof, &challenge)
+        .map_err(|e| format!("constraint sumcheck: {}", e))?;
+
+    // 11'. ring switching through Tr_H to S ≅ Fq4: the same constraint on w = z only
+    // (no quotient rows), for a verifier challenge c ∈ R_q
+    let sub = Subring::new();
+    let c: Vec<Fq> = (0..N).map(|_| Fq::rand(&mut rng)).collect();
+    let eq_tau = mle_eq_block_table::<Fq4>(5, &tau);
+    let phi = switch_rows(&sub, &m, &eq_tau, &c);
+    let f_switched = build_f_table_switched(&mle_w_range.evaluations, &phi, 5, 6);
+    let a_sw = switched_claim(&sub, &y, &eq_tau, &c);
+    let challenge_sw: [Fq4; 11] = std::array::from_fn(|_| Fq4::rand(&mut rng));
+    let proof_sw = sumcheck_prove_from_table(f_switched.clone(), &challenge_sw);
+
+    sumcheck_verify_from_table(f_switched, a_sw, &proof_sw, &challenge_sw)
+        .map_err(|e| format!("switched sumcheck: {}", e))?;
 
     // verifier_time += start.elapsed();
     // println!("Prover 3: {:?}", prover_time3);
@@ -303,34 +300,12 @@ fn main() {
     // proof
     let proof_range = sumcheck_prove_from_table_range(range_table.clone(), &challenge_range);
 
-    let mut layer_range = range_table;
-    for (round, (&(c0,c1), &r)) in proof_range.g_coeffs.iter().zip(challenge_range.iter()).enumerate() {
-        // g_t(0) + g_t(1) = (c0) + (c0+c1) = 2*c0 + c1
-        let lhs_round_sum = layer_range.iter().copied().fold(Fq::zero(), |acc,x| acc + x);
-        let rhs_round_sum = c0 + (c0 + c1);
-        if round == 0{
-            assert_eq!(lhs_round_sum, Fq::zero(), "range: init sum check failed");
-        }
-        assert_eq!(lhs_round_sum, rhs_round_sum, "range: round {} sum check failed", round);
-
-        // verifier_time += start.elapsed();
-        // start = Instant::now();
-
-        let (_, next) = sumcheck_round_once_range(&layer_range, r);
-
-        // prover_time3 += start.elapsed();
-        // start = Instant::now();
-
-        let g_at_r = c0 + c1 * r;
-        let next_sum = next.iter().copied().fold(Fq::zero(), |acc,x| acc + x);
-        assert_eq!(g_at_r, next_sum, "range: round {} eval check failed", round);
-        layer_range = next;
-    }
-
-    assert_eq!(layer_range[0], proof_range.final_eval);
+    sumcheck_verify_from_table(range_table, Fq::zero(), &proof_range, &challenge_range)
+        .map_err(|e| format!("range sumcheck: {}", e))?;
     
 
 
     // // 13. PCS open
-    
+
+    Ok(())
 }
\ No newline at end of file
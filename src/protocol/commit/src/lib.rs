@@ -1,13 +1,13 @@
 //! Inner+outer commitments per Sec. 2.5 (Eq. (4)) and Commit/Open in Fig. 4.  :contentReference[oaicite:3]{index=3}
 
-use greyhound_ring::{ModQ, Poly, D};
+use greyhound_ring::{DecodeError, ModQ, Poly, Reader, SecretPolyVec, Writer, D};
 use greyhound_gadget::{digits_for, g_inv_vec, g_fwd_vec};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
 pub type PolyVec = Vec<Poly>;
 
 /// Simple dense matrix over R_q, stored row-major.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct MatrixRq {
     pub rows: usize,
     pub cols: usize,
@@ -15,9 +15,11 @@ pub struct MatrixRq {
 }
 
 impl MatrixRq {
-    pub fn new(rows: usize, cols: usize, data: Vec<Poly>) -> Self {
-        assert_eq!(data.len(), rows * cols);
-        Self { rows, cols, data }
+    pub fn new(rows: usize, cols: usize, data: Vec<Poly>) -> Result<Self, OpenError> {
+        if data.len() != rows * cols {
+            return Err(OpenError::Dimension { what: "matrix data", index: None, expected: rows * cols, got: data.len() });
+        }
+        Ok(Self { rows, cols, data })
     }
     pub fn at(&self, r: usize, c: usize) -> &Poly {
         &self.data[r * self.cols + c]
@@ -34,6 +36,17 @@ impl MatrixRq {
         }
         out
     }
+    /// M·x for x given by its nonzero entries (index, value): touches only those columns.
+    pub fn mul_vec_sparse(&self, x: &[(usize, Poly)], q: &ModQ) -> PolyVec {
+        let mut out = vec![Poly::zero(); self.rows];
+        for (c, xc) in x {
+            assert!(*c < self.cols);
+            for (r, o) in out.iter_mut().enumerate() {
+                *o = o.add(&self.at(r, *c).mul(xc, q), q);
+            }
+        }
+        out
+    }
     /// Uniform random matrix (toy RNG; swap with CSPRNG later).
     pub fn random(rows: usize, cols: usize, q: &ModQ, rng: &mut StdRng) -> Self {
         let mut data = Vec::with_capacity(rows * cols);
@@ -42,10 +55,112 @@ impl MatrixRq {
             for j in 0..D { coeffs[j] = rng.gen::<u32>() % q.q; }
             data.push(Poly::from_coeffs(coeffs, q));
         }
-        Self { rows, cols, data }
+        Self::new(rows, cols, data).expect("rows·cols entries")
+    }
+    /// Columns [c0, c1) as a new matrix.
+    pub fn col_range(&self, c0: usize, c1: usize) -> Self {
+        assert!(c0 <= c1 && c1 <= self.cols);
+        let mut data = Vec::with_capacity(self.rows * (c1 - c0));
+        for r in 0..self.rows {
+            data.extend_from_slice(&self.data[r * self.cols + c0 .. r * self.cols + c1]);
+        }
+        Self::new(self.rows, c1 - c0, data).expect("rows·cols entries")
+    }
+    pub fn col_prefix(&self, cols: usize) -> Self {
+        self.col_range(0, cols)
+    }
+    /// Horizontal concatenation [self | other].
+    pub fn hcat(&self, other: &Self) -> Self {
+        assert_eq!(self.rows, other.rows);
+        let cols = self.cols + other.cols;
+        let mut data = Vec::with_capacity(self.rows * cols);
+        for r in 0..self.rows {
+            data.extend_from_slice(&self.data[r * self.cols .. (r + 1) * self.cols]);
+            data.extend_from_slice(&other.data[r * other.cols .. (r + 1) * other.cols]);
+        }
+        Self::new(self.rows, cols, data).expect("rows·cols entries")
+    }
+}
+
+/// A matrix preprocessed for repeated products: entry a is kept as its negacyclic
+/// table [−a | a] (2D residues), so each output coefficient of M·x is one dot product
+/// accumulated in u128 and reduced once, instead of a reduction per ring product.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparedMatrix {
+    pub rows: usize,
+    pub cols: usize,
+    table: Vec<[u32; 2 * D]>,
+}
+
+impl PreparedMatrix {
+    pub fn new(m: &MatrixRq, q: &ModQ) -> Self {
+        let table = m.data.iter().map(|a| {
+            let mut t = [0u32; 2 * D];
+            for i in 0..D { t[i] = q.neg(a.c[i]); t[D + i] = a.c[i]; }
+            t
+        }).collect();
+        Self { rows: m.rows, cols: m.cols, table }
+    }
+
+    /// Same result as `MatrixRq::mul_vec` on the matrix this was built from.
+    pub fn mul_vec(&self, x: &PolyVec, q: &ModQ) -> PolyVec {
+        assert_eq!(x.len(), self.cols);
+        (0..self.rows).map(|r| {
+            let mut acc = [0u128; D];
+            for (t, xc) in self.table[r * self.cols..(r + 1) * self.cols].iter().zip(x) {
+                for (j, &xj) in xc.c.iter().enumerate() {
+                    if xj == 0 { continue; }
+                    // X^j · a: coefficient k is a_{k−j}, or −a_{k−j+D} when k < j
+                    for (k, acc_k) in acc.iter_mut().enumerate() { *acc_k += t[k + D - j] as u128 * xj as u128; }
+                }
+            }
+            Poly { c: acc.map(|a| (a % q.q as u128) as u32) }
+        }).collect()
+    }
+}
+
+/// The matrices of `CommitParams` (A, B, tiers, E) as `PreparedMatrix`, for a prover
+/// that commits many times under the same params. Only valid for the params it was
+/// built from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prepared {
+    A: PreparedMatrix,
+    B: PreparedMatrix,
+    tiers: Vec<PreparedMatrix>,
+    E: Option<PreparedMatrix>,
+}
+
+impl Prepared {
+    pub fn new(pp: &CommitParams) -> Self {
+        let prep = |m: &MatrixRq| PreparedMatrix::new(m, &pp.q);
+        Prepared {
+            A: prep(&pp.A),
+            B: prep(&pp.B),
+            tiers: pp.tiers.iter().map(|t| prep(&t.M)).collect(),
+            E: pp.E.as_ref().map(prep),
+        }
+    }
+}
+
+// m·x through its prepared form when there is one.
+fn mul_with(m: &MatrixRq, p: Option<&PreparedMatrix>, x: &PolyVec, q: &ModQ) -> PolyVec {
+    match p {
+        Some(p) => p.mul_vec(x, q),
+        None => m.mul_vec(x, q),
     }
 }
 
+/// One extra outer level of a k-tier commitment tree: the previous level's decomposed
+/// nodes are taken `arity` at a time and each group is re-committed with M, giving the
+/// next level's nodes (n polys each), which are then decomposed in base b.
+#[derive(Clone)]
+pub struct TierParams {
+    pub arity: usize,
+    pub b: u32,
+    pub delta: usize,
+    pub M: MatrixRq,              // n x (n*delta_in*arity)
+}
+
 /// Public parameters for commitments (A,B) and bases b0,b1.
 #[derive(Clone)]
 pub struct CommitParams {
@@ -61,6 +176,10 @@ pub struct CommitParams {
     pub B: MatrixRq,                // n x (n*delta1*r)
     pub mu: usize,                // LWE rank for outer randomness
     pub E: Option<MatrixRq>,      // when Some, the scheme is hiding
+    pub b_seed: Option<u64>,      // when Some, B is seed-expanded per column and can grow
+    pub r_bound_sq: u128,         // ‖r‖₂² bound for the hiding randomness
+    pub tiers: Vec<TierParams>,   // k-2 levels between \hat t and B (empty: classic two levels)
+    pub msg_bound: Option<u32>,   // when Some(β), messages with ‖f_i‖∞ ≤ β are committed as-is
 }
 
 impl CommitParams {
@@ -71,126 +190,653 @@ impl CommitParams {
         let A = MatrixRq::random(n, delta0 * m, &q, &mut rng);
         let B = MatrixRq::random(n, n * delta1 * r, &q, &mut rng);
         // default: non-hiding
-        Self { q, n, m, r, delta0, delta1, b0, b1, A, B, mu: 0, E: None }
+        Self { q, n, m, r, delta0, delta1, b0, b1, A, B, mu: 0, E: None, b_seed: None, r_bound_sq: 0, tiers: Vec::new(), msg_bound: None }
+    }
+
+    /// Appendable variant: B = [B_0 | B_1 | …] where each n x (n*delta1) block B_i
+    /// is expanded from (seed, i), so new columns f_{r+1}, … can be committed later
+    /// without changing the blocks already in use.
+    pub fn gen_appendable(q: ModQ, n: usize, m: usize, r: usize, b0: u32, b1: u32, seed: u64) -> Self {
+        let delta0 = digits_for(&q, b0);
+        let delta1 = digits_for(&q, b1);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let A = MatrixRq::random(n, delta0 * m, &q, &mut rng);
+        let mut B = MatrixRq::zeros(n, 0);
+        for i in 0..r { B = B.hcat(&b_block(&q, n, delta1, seed, i)); }
+        Self { q, n, m, r, delta0, delta1, b0, b1, A, B, mu: 0, E: None, b_seed: Some(seed), r_bound_sq: 0, tiers: Vec::new(), msg_bound: None }
+    }
+
+    /// k-level commitment tree: t_i = A s_i, then k-2 tiers that each decompose and
+    /// re-commit groups of the previous level, and finally u = B · (top level, decomposed).
+    /// Every tier uses base b1 and arity ⌈r^{1/(k-1)}⌉, and the tiers and the new B
+    /// are expanded from `seed`; depth 2 leaves `self` as it is.
+    pub fn with_depth(mut self, depth: usize, seed: u64) -> Result<Self, OpenError> {
+        if depth < 2 { return Err(OpenError::Depth { depth }); }
+        if depth == 2 { return Ok(self); }
+        let (n, b1, delta1) = (self.n, self.b1, self.delta1);
+        let arity = tier_arity(self.r, depth);
+        self.tiers = (0..depth - 2)
+            .map(|j| TierParams { arity, b: b1, delta: delta1, M: expand_block(&self.q, n, n * delta1 * arity, seed ^ 0x7AE5, j) })
+            .collect();
+        let top = *self.level_nodes().last().unwrap();
+        self.B = expand_block(&self.q, n, n * self.top_delta() * top, seed ^ 0x7AE5, depth - 2);
+        self.b_seed = None;
+        Ok(self)
     }
 
-    /// Hiding extension: choose μ and E.
+    /// Number of commitment levels k (inner A, tiers, outer B).
+    pub fn depth(&self) -> usize { self.tiers.len() + 2 }
+
+    /// Node count per outer level: [r, ⌈r/a_0⌉, …]; the last entry is what B covers.
+    pub fn level_nodes(&self) -> Vec<usize> {
+        let mut nodes = vec![self.r];
+        for t in &self.tiers {
+            let prev = *nodes.last().unwrap();
+            nodes.push(prev.div_ceil(t.arity));
+        }
+        nodes
+    }
+
+    /// Gadget digits of the level B is applied to.
+    pub fn top_delta(&self) -> usize {
+        self.tiers.last().map_or(self.delta1, |t| t.delta)
+    }
+
+    /// Total length of the tier decompositions (levels 2..k-1) kept in `Decommit::tiers`.
+    pub fn tier_witness_len(&self) -> usize {
+        let nodes = self.level_nodes();
+        self.tiers.iter().enumerate().map(|(j, t)| self.n * t.delta * nodes[j + 1]).sum()
+    }
+
+    /// Grow an appendable B to `new_r` column blocks (no-op if already that wide).
+    pub fn grow(&mut self, new_r: usize) -> Result<(), OpenError> {
+        let seed = self.b_seed.filter(|_| self.tiers.is_empty()).ok_or(OpenError::NotAppendable)?;
+        for i in self.r..new_r {
+            self.B = self.B.hcat(&b_block(&self.q, self.n, self.delta1, seed, i));
+        }
+        self.r = self.r.max(new_r);
+        Ok(())
+    }
+
+    /// Parameters for the commitment version with `r` columns: B restricted to its
+    /// first r column blocks. Openings and proofs made for that version check against these.
+    pub fn at_version(&self, r: usize) -> Result<Self, OpenError> {
+        if self.b_seed.is_none() { return Err(OpenError::NotAppendable); }
+        if r > self.r { return Err(OpenError::Version { requested: r, latest: self.r }); }
+        let mut out = self.clone();
+        out.B = self.B.col_prefix(r * self.n * self.delta1);
+        out.r = r;
+        Ok(out)
+    }
+
+    /// Short-message mode: messages declared to satisfy ‖f_i‖∞ ≤ β skip G^{-1}_{b0}
+    /// and are committed directly, t_i = A f_i with A of shape n x m. Internally this is
+    /// the b0 = 2β+1, δ0 = 1 gadget, so s_i = f_i and the opening/relation shapes follow.
+    pub fn with_short_messages(mut self, beta: u32, seed: u64) -> Result<Self, OpenError> {
+        let b0 = beta.checked_mul(2).and_then(|b| b.checked_add(1)).ok_or(OpenError::MessageBound { beta })?;
+        let mut rng = StdRng::seed_from_u64(seed ^ 0x5407);
+        self.A = MatrixRq::random(self.n, self.m, &self.q, &mut rng);
+        self.b0 = b0;
+        self.delta0 = 1;
+        self.msg_bound = Some(beta);
+        Ok(self)
+    }
+
+    /// Hiding extension: choose μ and E. r is ternary, so ‖r‖₂² ≤ μ·d.
     pub fn with_hiding(mut self, mu: usize, seed: u64) -> Self {
         if mu > 0 {
             let mut rng = StdRng::seed_from_u64(seed ^ 0xE11E);
             self.E = Some(MatrixRq::random(self.n, mu, &self.q, &mut rng));
             self.mu = mu;
+            self.r_bound_sq = (mu * D) as u128;
         }
         self
     }
 }
 
-#[derive(Clone)]
+/// s_i for message column `col`: G^{-1}_{b0,m}(f_i), or f_i itself in short-message
+/// mode, where an entry above the declared bound β is a `DigitBound` error on s_col.
+pub fn message_digits(pp: &CommitParams, col: usize, f_i: &PolyVec) -> Result<PolyVec, OpenError> {
+    match pp.msg_bound {
+        Some(beta) => {
+            check_digits(Part::S(col), f_i, beta as u64, &pp.q)?;
+            Ok(f_i.clone())
+        }
+        None => Ok(g_inv_vec(f_i, pp.b0, &pp.q)),
+    }
+}
+
+/// Per-tier arity ⌈r^{1/(depth-1)}⌉, so depth-1 outer levels shrink r nodes to about one group.
+pub fn tier_arity(r: usize, depth: usize) -> usize {
+    let levels = (depth - 1) as u32;
+    let mut a = 1usize;
+    // an overflowing a^levels is certainly ≥ r
+    while a.checked_pow(levels).is_some_and(|p| p < r) { a += 1; }
+    a.max(2)
+}
+
+/// Apply the tiers to level-1 \hat t: returns the decomposed nodes of levels 2..k-1.
+pub fn tier_hats(pp: &CommitParams, that: &[Poly]) -> Vec<PolyVec> {
+    tier_hats_with(pp, None, that)
+}
+
+fn tier_hats_with(pp: &CommitParams, prep: Option<&Prepared>, that: &[Poly]) -> Vec<PolyVec> {
+    let mut out: Vec<PolyVec> = Vec::with_capacity(pp.tiers.len());
+    let mut delta_in = pp.delta1;
+    for (j, t) in pp.tiers.iter().enumerate() {
+        let prev: &[Poly] = out.last().map_or(that, |v| v.as_slice());
+        let width = pp.n * delta_in * t.arity;
+        let mut nodes: PolyVec = Vec::new();
+        for chunk in prev.chunks(width) {
+            let mut x = chunk.to_vec();
+            x.resize(width, Poly::zero());
+            nodes.extend(mul_with(&t.M, prep.map(|p| &p.tiers[j]), &x, &pp.q));
+        }
+        out.push(g_inv_vec(&nodes, t.b, &pp.q));
+        delta_in = t.delta;
+    }
+    out
+}
+
+/// Column block i of an appendable B: n x (n*delta1), a function of (seed, i) only.
+pub fn b_block(q: &ModQ, n: usize, delta1: usize, seed: u64, i: usize) -> MatrixRq {
+    expand_block(q, n, n * delta1, seed ^ 0xB10C, i)
+}
+
+/// The i-th rows x cols block of a seed-expanded matrix that grows by whole blocks.
+pub fn expand_block(q: &ModQ, rows: usize, cols: usize, seed: u64, i: usize) -> MatrixRq {
+    let mut rng = StdRng::seed_from_u64(seed ^ (i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    MatrixRq::random(rows, cols, q, &mut rng)
+}
+
+/// Which part of a decommitment a failed check refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    S(usize),   // s_i, column i
+    That,       // \hat t (concatenated)
+    Tier(usize), // decomposed nodes of tier j (level j+2)
+}
+
+/// Why a commit input or an opening was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpenError {
+    /// Wrong length of `what` ("f", "s", "that", "u", "r"), or of its entry `index`.
+    Dimension { what: &'static str, index: Option<usize>, expected: usize, got: usize },
+    /// Coefficient `coeff` of entry `index` of `part` is outside [-bound, bound].
+    DigitBound { part: Part, index: usize, coeff: usize, value: i64, bound: u64 },
+    /// ‖r‖₂² is above the hiding bound.
+    RandomnessNorm { norm_sq: u128, bound_sq: u128 },
+    /// G_{b0,m} s_i ≠ f_i.
+    MessageMismatch { col: usize },
+    /// A s_i ≠ G_{b1,n} \hat t_i.
+    InnerMismatch { col: usize },
+    /// Tier j: M_j · (group `node` of the level below) ≠ G · (node `node` of level j+2).
+    TierMismatch { tier: usize, node: usize },
+    /// u ≠ B \hat t (+ E r).
+    OuterMismatch,
+    /// r is present in a non-hiding opening, or missing in a hiding one
+    /// (or hiding params are missing for commit_hiding).
+    HidingMismatch,
+    /// append(), grow() or at_version() on params without an appendable B.
+    NotAppendable,
+    /// Index `index` into `what` is not below `bound` (e.g. a sparse coefficient beyond N).
+    Index { what: &'static str, index: usize, bound: usize },
+    /// with_depth() with fewer than two levels.
+    Depth { depth: usize },
+    /// at_version() for a version newer than the params.
+    Version { requested: usize, latest: usize },
+    /// with_short_messages() with a β whose base 2β+1 does not fit in u32.
+    MessageBound { beta: u32 },
+}
+
+impl std::fmt::Display for OpenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenError::Dimension { what, index: None, expected, got } =>
+                write!(f, "dimension mismatch in {}: expected {}, got {}", what, expected, got),
+            OpenError::Dimension { what, index: Some(i), expected, got } =>
+                write!(f, "dimension mismatch in {}[{}]: expected {}, got {}", what, i, expected, got),
+            OpenError::DigitBound { part, index, coeff, value, bound } =>
+                write!(f, "{:?}[{}] coefficient {} = {} exceeds digit bound {}", part, index, coeff, value, bound),
+            OpenError::RandomnessNorm { norm_sq, bound_sq } =>
+                write!(f, "‖r‖₂² = {} exceeds bound {}", norm_sq, bound_sq),
+            OpenError::MessageMismatch { col } => write!(f, "G s_{} ≠ f_{}", col, col),
+            OpenError::InnerMismatch { col } => write!(f, "A s_{} ≠ G \\hat t_{}", col, col),
+            OpenError::TierMismatch { tier, node } => write!(f, "tier {} node {} does not match its group", tier, node),
+            OpenError::OuterMismatch => write!(f, "u does not match the outer commitment"),
+            OpenError::HidingMismatch => write!(f, "hiding randomness present/absent unexpectedly"),
+            OpenError::NotAppendable => write!(f, "params were not generated with gen_appendable()"),
+            OpenError::Index { what, index, bound } => write!(f, "{} index {} is not below {}", what, index, bound),
+            OpenError::Depth { depth } => write!(f, "a commitment tree has at least two levels, got {}", depth),
+            OpenError::Version { requested, latest } =>
+                write!(f, "version {} is newer than these params ({})", requested, latest),
+            OpenError::MessageBound { beta } => write!(f, "short-message bound {} gives a base 2β+1 above u32", beta),
+        }
+    }
+}
+
+impl std::error::Error for OpenError {}
+
+/// Check every coefficient of `v` lies in [-bound, bound].
+fn check_digits(part: Part, v: &[Poly], bound: u64, q: &ModQ) -> Result<(), OpenError> {
+    for (index, p) in v.iter().enumerate() {
+        for (coeff, &x) in p.c.iter().enumerate() {
+            let value = q.signed(x);
+            if value.unsigned_abs() > bound {
+                return Err(OpenError::DigitBound { part, index, coeff, value, bound });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Deliberately not `Clone`: copies of the opening would escape the wipe on drop.
 pub struct Decommit {
     pub s: Vec<PolyVec>,   // as before
     pub that: PolyVec,     // as before
-    pub r: Option<PolyVec> // new: randomness for hiding (length μ) if hiding
+    pub r: Option<PolyVec>, // new: randomness for hiding (length μ) if hiding
+    pub tiers: Vec<PolyVec>, // decomposed nodes of levels 2..k-1 (empty for two levels)
+}
+
+/// Decommitments are secret: with the `zeroize` feature they are wiped on drop.
+impl Drop for Decommit {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Decommit {
+    fn zeroize(&mut self) {
+        self.s.zeroize();
+        self.that.zeroize();
+        self.r.zeroize();
+        self.tiers.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Decommit {}
+
+impl Decommit {
+    /// The decomposed level B is applied to.
+    pub fn top(&self) -> &PolyVec {
+        self.tiers.last().unwrap_or(&self.that)
+    }
+}
+
+impl Decommit {
+    /// Append the decommitment of newly added columns (see `append`). \hat t grows
+    /// through `SecretPolyVec::append_to`; s only moves the column buffers themselves.
+    pub fn extend(&mut self, mut more: Decommit) {
+        self.s.append(&mut more.s);
+        SecretPolyVec::append_to(&mut self.that, std::mem::take(&mut more.that));
+    }
 }
 
-#[derive(Clone)]
 pub struct Commitment {
     pub u: PolyVec,        // length n
     pub dec: Decommit,
 }
 
-/// Non-hiding commit: u = B * \hat t
-pub fn commit(pp: &CommitParams, f_cols: &[PolyVec]) -> Commitment {
-    assert_eq!(f_cols.len(), pp.r);
-    for col in f_cols { assert_eq!(col.len(), pp.m); }
+// f must have `expected` columns of length m.
+fn check_message(pp: &CommitParams, f_cols: &[PolyVec], expected: usize) -> Result<(), OpenError> {
+    if f_cols.len() != expected {
+        return Err(OpenError::Dimension { what: "f", index: None, expected, got: f_cols.len() });
+    }
+    match f_cols.iter().position(|col| col.len() != pp.m) {
+        Some(i) => Err(OpenError::Dimension { what: "f", index: Some(i), expected: pp.m, got: f_cols[i].len() }),
+        None => Ok(()),
+    }
+}
 
-    // s_i := G^{-1}_{b0,m}(f_i),  t_i := A s_i,  \hat t_i := G^{-1}_{b1,n}(t_i)
+// s_i := G^{-1}_{b0,m}(f_i),  t_i := A s_i,  \hat t_i := G^{-1}_{b1,n}(t_i)
+fn inner_commit(pp: &CommitParams, prep: Option<&Prepared>, f_cols: &[PolyVec]) -> Result<(Vec<PolyVec>, PolyVec), OpenError> {
+    check_message(pp, f_cols, pp.r)?;
     let mut s_all = Vec::with_capacity(pp.r);
     let mut that_concat: PolyVec = Vec::with_capacity(pp.n * pp.delta1 * pp.r);
     for i in 0..pp.r {
-        let si = greyhound_gadget::g_inv_vec(&f_cols[i], pp.b0, &pp.q);
-        let ti = pp.A.mul_vec(&si, &pp.q);
-        let that_i = greyhound_gadget::g_inv_vec(&ti, pp.b1, &pp.q);
+        let si = message_digits(pp, i, &f_cols[i])?;
+        let ti = SecretPolyVec::new(mul_with(&pp.A, prep.map(|p| &p.A), &si, &pp.q));
+        let that_i = SecretPolyVec::new(g_inv_vec(&ti, pp.b1, &pp.q));
         s_all.push(si);
-        that_concat.extend(that_i);
+        that_concat.extend_from_slice(&that_i);
     }
-    let u = pp.B.mul_vec(&that_concat, &pp.q);
-    Commitment { u, dec: Decommit { s: s_all, that: that_concat, r: None } }
+    Ok((s_all, that_concat))
+}
+
+/// Non-hiding commit: u = B * \hat t
+pub fn commit(pp: &CommitParams, f_cols: &[PolyVec]) -> Result<Commitment, OpenError> {
+    commit_with(pp, None, f_cols)
+}
+
+/// `commit` with the matrix products through `prep` (built from `pp`).
+pub fn commit_prepared(pp: &CommitParams, prep: &Prepared, f_cols: &[PolyVec]) -> Result<Commitment, OpenError> {
+    commit_with(pp, Some(prep), f_cols)
 }
 
-/// Non-hiding open check (Eq. (4))
-pub fn open_check(pp: &CommitParams, u: &PolyVec, f_cols: &[PolyVec], dec: &Decommit) -> bool {
-    if dec.r.is_some() { return false; }                 // should be None in non-hiding
-    if dec.s.len() != pp.r { return false; }
-    if dec.that.len() != pp.n * pp.delta1 * pp.r { return false; }
-    if u.len() != pp.n { return false; }
+fn commit_with(pp: &CommitParams, prep: Option<&Prepared>, f_cols: &[PolyVec]) -> Result<Commitment, OpenError> {
+    let (s_all, that_concat) = inner_commit(pp, prep, f_cols)?;
+    let tiers = tier_hats_with(pp, prep, &that_concat);
+    let u = mul_with(&pp.B, prep.map(|p| &p.B), tiers.last().unwrap_or(&that_concat), &pp.q);
+    Ok(Commitment { u, dec: Decommit { s: s_all, that: that_concat, r: None, tiers } })
+}
+
+/// Append columns to a non-hiding appendable commitment:
+/// u' = u + Σ_{i ≥ r_old} B_i \hat t_i, where r_old = pp.r - f_new.len().
+/// Only the new \hat t blocks are computed; the returned decommitment covers the new
+/// columns and is meant to be `extend`ed onto the old one.
+pub fn append(pp: &CommitParams, u: &PolyVec, f_new: &[PolyVec]) -> Result<Commitment, OpenError> {
+    if pp.b_seed.is_none() { return Err(OpenError::NotAppendable); }
+    if u.len() != pp.n { return Err(OpenError::Dimension { what: "u", index: None, expected: pp.n, got: u.len() }); }
+    check_message(pp, f_new, f_new.len().min(pp.r))?;
+    let r_old = pp.r - f_new.len();
+
+    let mut s_new = Vec::with_capacity(f_new.len());
+    let mut that_new: PolyVec = Vec::with_capacity(pp.n * pp.delta1 * f_new.len());
+    for (i, f_i) in f_new.iter().enumerate() {
+        let si = message_digits(pp, r_old + i, f_i)?;
+        let ti = SecretPolyVec::new(pp.A.mul_vec(&si, &pp.q));
+        that_new.extend_from_slice(&SecretPolyVec::new(g_inv_vec(&ti, pp.b1, &pp.q)));
+        s_new.push(si);
+    }
+    let block = pp.n * pp.delta1;
+    let b_new = pp.B.col_range(r_old * block, pp.r * block);
+    let delta_u = b_new.mul_vec(&that_new, &pp.q);
+    let u_new = u.iter().zip(&delta_u).map(|(a, b)| a.add(b, &pp.q)).collect();
+    Ok(Commitment { u: u_new, dec: Decommit { s: s_new, that: that_new, r: None, tiers: Vec::new() } })
+}
+
+/// Shared part of both open checks: dimensions, digit bounds ‖s_i‖∞ ≤ b0/2 and
+/// ‖\hat t‖∞ ≤ b1/2, then G s_i = f_i and A s_i = G \hat t_i.
+fn check_inner(pp: &CommitParams, f_cols: &[PolyVec], dec: &Decommit) -> Result<(), OpenError> {
+    let (s, that) = (&dec.s, &dec.that);
+    if f_cols.len() != pp.r {
+        return Err(OpenError::Dimension { what: "f", index: None, expected: pp.r, got: f_cols.len() });
+    }
+    if s.len() != pp.r {
+        return Err(OpenError::Dimension { what: "s", index: None, expected: pp.r, got: s.len() });
+    }
+    if that.len() != pp.n * pp.delta1 * pp.r {
+        return Err(OpenError::Dimension { what: "that", index: None, expected: pp.n * pp.delta1 * pp.r, got: that.len() });
+    }
+    for i in 0..pp.r {
+        if s[i].len() != pp.delta0 * pp.m {
+            return Err(OpenError::Dimension { what: "s", index: Some(i), expected: pp.delta0 * pp.m, got: s[i].len() });
+        }
+        if f_cols[i].len() != pp.m {
+            return Err(OpenError::Dimension { what: "f", index: Some(i), expected: pp.m, got: f_cols[i].len() });
+        }
+    }
+
+    // Digit bounds: balanced base-b digits lie in [-⌊b/2⌋, ⌊b/2⌋]
+    for (i, si) in s.iter().enumerate() { check_digits(Part::S(i), si, (pp.b0 / 2) as u64, &pp.q)?; }
+    check_digits(Part::That, that, (pp.b1 / 2) as u64, &pp.q)?;
 
     // Check G_{b0,m} s_i = f_i
     for i in 0..pp.r {
-        if dec.s[i].len() != pp.delta0 * pp.m { return false; }
-        let fi_rec = greyhound_gadget::g_fwd_vec(&dec.s[i], pp.m, pp.b0, &pp.q);
-        if fi_rec != f_cols[i] { return false; }
+        let fi_rec = greyhound_gadget::g_fwd_vec(&s[i], pp.m, pp.b0, &pp.q);
+        if fi_rec != f_cols[i] { return Err(OpenError::MessageMismatch { col: i }); }
     }
     // Check A s_i = G_{b1,n} \hat t_i
     let block = pp.n * pp.delta1;
     for i in 0..pp.r {
-        let that_i = &dec.that[i*block .. (i+1)*block];
+        let that_i = &that[i*block .. (i+1)*block];
         let ti_rec = greyhound_gadget::g_fwd_vec(that_i, pp.n, pp.b1, &pp.q);
-        let ti_from_A = pp.A.mul_vec(&dec.s[i], &pp.q);
-        if ti_rec != ti_from_A { return false; }
+        let ti_from_a = pp.A.mul_vec(&s[i], &pp.q);
+        if ti_rec != ti_from_a { return Err(OpenError::InnerMismatch { col: i }); }
+    }
+    check_tiers(pp, dec)
+}
+
+/// Tier checks of a k-level tree: digit bounds on each level's decomposition and
+/// M_j · (group g of level j+1) = G · (node g of level j+2).
+fn check_tiers(pp: &CommitParams, dec: &Decommit) -> Result<(), OpenError> {
+    if dec.tiers.len() != pp.tiers.len() {
+        return Err(OpenError::Dimension { what: "tiers", index: None, expected: pp.tiers.len(), got: dec.tiers.len() });
+    }
+    let nodes = pp.level_nodes();
+    let mut prev: &PolyVec = &dec.that;
+    let mut delta_in = pp.delta1;
+    for (j, t) in pp.tiers.iter().enumerate() {
+        let hats = &dec.tiers[j];
+        let len = pp.n * t.delta * nodes[j + 1];
+        if hats.len() != len {
+            return Err(OpenError::Dimension { what: "tiers", index: Some(j), expected: len, got: hats.len() });
+        }
+        check_digits(Part::Tier(j), hats, (t.b / 2) as u64, &pp.q)?;
+        let width = pp.n * delta_in * t.arity;
+        for (g, chunk) in prev.chunks(width).enumerate() {
+            let mut x = chunk.to_vec();
+            x.resize(width, Poly::zero());
+            let node = &hats[g * pp.n * t.delta .. (g + 1) * pp.n * t.delta];
+            if t.M.mul_vec(&x, &pp.q) != g_fwd_vec(node, pp.n, t.b, &pp.q) {
+                return Err(OpenError::TierMismatch { tier: j, node: g });
+            }
+        }
+        prev = hats;
+        delta_in = t.delta;
     }
+    Ok(())
+}
+
+/// Non-hiding open check (Eq. (4)), including the digit norm bounds binding relies on.
+pub fn open_check(pp: &CommitParams, u: &PolyVec, f_cols: &[PolyVec], dec: &Decommit) -> Result<(), OpenError> {
+    if dec.r.is_some() { return Err(OpenError::HidingMismatch); }  // should be None in non-hiding
+    if u.len() != pp.n { return Err(OpenError::Dimension { what: "u", index: None, expected: pp.n, got: u.len() }); }
+    check_inner(pp, f_cols, dec)?;
+
     // Check u = B \hat t
-    let u_chk = pp.B.mul_vec(&dec.that, &pp.q);
-    u_chk == *u
+    let u_chk = pp.B.mul_vec(dec.top(), &pp.q);
+    if u_chk != *u { return Err(OpenError::OuterMismatch); }
+    Ok(())
 }
 
+/// Hiding commit: u = B \hat t + E r, with r drawn from `rng`  (Sec. 4.5).  :contentReference[oaicite:2]{index=2}
+pub fn commit_hiding(pp: &CommitParams, f_cols: &[PolyVec], rng: &mut StdRng) -> Result<Commitment, OpenError> {
+    commit_hiding_with(pp, None, f_cols, rng)
+}
 
-/// Hiding commit: u = B \hat t + E r   (Sec. 4.5).  :contentReference[oaicite:2]{index=2}
-pub fn commit_hiding(pp: &CommitParams, f_cols: &[PolyVec]) -> Commitment {
-    assert!(pp.E.is_some() && pp.mu > 0, "call with_hiding() first");
-    assert_eq!(f_cols.len(), pp.r);
-    for col in f_cols { assert_eq!(col.len(), pp.m); }
+/// `commit_hiding` with the matrix products through `prep` (built from `pp`).
+pub fn commit_hiding_prepared(pp: &CommitParams, prep: &Prepared, f_cols: &[PolyVec], rng: &mut StdRng) -> Result<Commitment, OpenError> {
+    commit_hiding_with(pp, Some(prep), f_cols, rng)
+}
 
+fn commit_hiding_with(pp: &CommitParams, prep: Option<&Prepared>, f_cols: &[PolyVec], rng: &mut StdRng) -> Result<Commitment, OpenError> {
+    let E = match &pp.E {
+        Some(E) if pp.mu > 0 => E,
+        _ => return Err(OpenError::HidingMismatch),  // call with_hiding() first
+    };
     // same as non-hiding path: s_i, t_i, \hat t_i
-    let mut s_all = Vec::with_capacity(pp.r);
-    let mut that_concat: PolyVec = Vec::with_capacity(pp.n * pp.delta1 * pp.r);
-    for i in 0..pp.r {
-        let si = g_inv_vec(&f_cols[i], pp.b0, &pp.q);
-        let ti = pp.A.mul_vec(&si, &pp.q);
-        let that_i = g_inv_vec(&ti, pp.b1, &pp.q);
-        s_all.push(si);
-        that_concat.extend(that_i);
-    }
+    let (s_all, that_concat) = inner_commit(pp, prep, f_cols)?;
 
-    // r ∈ R_q^μ (toy: uniform; later swap for narrow mod-b)
-    let mut rng = StdRng::seed_from_u64(0xC001);
+    // r ∈ R_q^μ, fresh ternary coefficients so that ‖r‖₂² ≤ μ·d = r_bound_sq
     let mut r = Vec::with_capacity(pp.mu);
     for _ in 0..pp.mu {
-        let mut c = [0u32; D];
-        for t in 0..D { c[t] = rng.gen::<u32>() % pp.q.q; }
+        let c = std::array::from_fn(|_| match rng.gen_range(0..3u32) { 0 => 0, 1 => 1, _ => pp.q.q - 1 });
         r.push(Poly { c });
     }
 
-    // u = B \hat t + E r
-    let mut u = pp.B.mul_vec(&that_concat, &pp.q);
-    let Er = pp.E.as_ref().unwrap().mul_vec(&r, &pp.q);
-    for i in 0..u.len() { u[i] = u[i].add(&Er[i], &pp.q); }
+    // u = B \hat t + E r  (\hat t of the top level when there are tiers)
+    let tiers = tier_hats_with(pp, prep, &that_concat);
+    let mut u = mul_with(&pp.B, prep.map(|p| &p.B), tiers.last().unwrap_or(&that_concat), &pp.q);
+    let e_r = mul_with(E, prep.and_then(|p| p.E.as_ref()), &r, &pp.q);
+    for i in 0..u.len() { u[i] = u[i].add(&e_r[i], &pp.q); }
 
-    Commitment { u, dec: Decommit { s: s_all, that: that_concat, r: Some(r) } }
+    Ok(Commitment { u, dec: Decommit { s: s_all, that: that_concat, r: Some(r), tiers } })
 }
 
-pub fn open_check_hiding(pp: &CommitParams, u: &PolyVec, f_cols: &[PolyVec], dec: &Decommit) -> bool {
-    if pp.E.is_none() || pp.mu == 0 || dec.r.is_none() { return false; }
-    // reuse algebraic checks from non-hiding
-    if !open_check(pp, u, f_cols, &Decommit { s: dec.s.clone(), that: dec.that.clone(), r: None }) { return false; }
+/// Hiding open check: the non-hiding checks on (s, \hat t), plus ‖r‖₂² ≤ r_bound_sq
+/// and u = B \hat t + E r.
+pub fn open_check_hiding(pp: &CommitParams, u: &PolyVec, f_cols: &[PolyVec], dec: &Decommit) -> Result<(), OpenError> {
+    let (E, r) = match (pp.E.as_ref(), dec.r.as_ref()) {
+        (Some(E), Some(r)) if pp.mu > 0 => (E, r),
+        _ => return Err(OpenError::HidingMismatch),
+    };
+    if u.len() != pp.n { return Err(OpenError::Dimension { what: "u", index: None, expected: pp.n, got: u.len() }); }
+    if r.len() != pp.mu { return Err(OpenError::Dimension { what: "r", index: None, expected: pp.mu, got: r.len() }); }
+    check_inner(pp, f_cols, dec)?;
+
+    let norm_sq: u128 = r.iter().map(|p| p.l2_sq(&pp.q)).sum();
+    if norm_sq > pp.r_bound_sq {
+        return Err(OpenError::RandomnessNorm { norm_sq, bound_sq: pp.r_bound_sq });
+    }
 
     // check E r == u - B \hat t
-    let Er = pp.E.as_ref().unwrap().mul_vec(dec.r.as_ref().unwrap(), &pp.q);
-    let Bu = pp.B.mul_vec(&dec.that, &pp.q);
+    let e_r = E.mul_vec(r, &pp.q);
+    let b_top = pp.B.mul_vec(dec.top(), &pp.q);
     for i in 0..u.len() {
-        if u[i].sub(&Bu[i], &pp.q) != Er[i] { return false; }
+        if u[i].sub(&b_top[i], &pp.q) != e_r[i] { return Err(OpenError::OuterMismatch); }
     }
-    true
+    Ok(())
+}
+
+// ---- Wire encoding (format in greyhound_ring) ----
+
+const MATRIX_MAGIC: &[u8; 4] = b"GHMX";
+
+// Product of two decoded dimensions; an overflow is a malformed input, not a panic.
+fn dim(a: usize, b: usize) -> Result<usize, DecodeError> {
+    a.checked_mul(b).ok_or(DecodeError::Invalid("dimension overflows"))
 }
+const PARAMS_MAGIC: &[u8; 4] = b"GHCP";
+
+impl MatrixRq {
+    /// rows, cols, then the entries row-major.
+    pub fn write(&self, w: &mut Writer) {
+        w.put_len(self.rows).put_len(self.cols);
+        for p in &self.data { w.put_poly(p); }
+    }
+
+    /// Reads a matrix that must be rows x cols; `what` names it in errors.
+    pub fn read(rd: &mut Reader, what: &'static str, rows: usize, cols: usize) -> Result<Self, DecodeError> {
+        let (r, c) = (rd.get_u32()? as usize, rd.get_u32()? as usize);
+        if r != rows { return Err(DecodeError::Dimension { what, expected: rows, got: r }); }
+        if c != cols { return Err(DecodeError::Dimension { what, expected: cols, got: c }); }
+        let len = dim(rows, cols)?;
+        rd.check_fits(len, 4 * D)?;
+        let data = (0..len).map(|_| rd.get_poly()).collect::<Result<_, _>>()?;
+        Ok(Self::new(rows, cols, data).expect("rows·cols entries"))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(MATRIX_MAGIC);
+        self.write(&mut w);
+        w.finish()
+    }
+
+    /// Decodes a rows x cols matrix over R_q.
+    pub fn from_bytes(bytes: &[u8], q: &ModQ, rows: usize, cols: usize) -> Result<Self, DecodeError> {
+        let mut rd = Reader::new(bytes, MATRIX_MAGIC, Some(*q))?;
+        let m = Self::read(&mut rd, "matrix", rows, cols)?;
+        rd.finish()?;
+        Ok(m)
+    }
+}
+
+impl CommitParams {
+    /// All fields in declaration order; options as a 0/1 flag and the value.
+    pub fn write(&self, w: &mut Writer) {
+        w.put_u32(self.q.q).put_len(self.n).put_len(self.m).put_len(self.r)
+            .put_len(self.delta0).put_len(self.delta1).put_u32(self.b0).put_u32(self.b1);
+        self.A.write(w);
+        self.B.write(w);
+        w.put_len(self.mu);
+        match &self.E {
+            Some(E) => { w.put_u8(1); E.write(w); }
+            None => { w.put_u8(0); }
+        }
+        match self.b_seed {
+            Some(seed) => { w.put_u8(1).put_u64(seed); }
+            None => { w.put_u8(0); }
+        }
+        w.put_u128(self.r_bound_sq).put_len(self.tiers.len());
+        for t in &self.tiers {
+            w.put_len(t.arity).put_u32(t.b).put_len(t.delta);
+            t.M.write(w);
+        }
+        match self.msg_bound {
+            Some(beta) => { w.put_u8(1).put_u32(beta); }
+            None => { w.put_u8(0); }
+        }
+    }
+
+    /// Reads params and checks the gadget digits and every matrix shape against n, m, r.
+    pub fn read(rd: &mut Reader) -> Result<Self, DecodeError> {
+        let q = rd.get_modulus()?;
+        let (n, m, r) = (rd.get_u32()? as usize, rd.get_u32()? as usize, rd.get_u32()? as usize);
+        if n == 0 || m == 0 || r == 0 { return Err(DecodeError::Invalid("zero dimension")); }
+        let (delta0, delta1) = (rd.get_u32()? as usize, rd.get_u32()? as usize);
+        let (b0, b1) = (rd.get_u32()?, rd.get_u32()?);
+        if b0 < 2 || b1 < 2 { return Err(DecodeError::Invalid("gadget base below 2")); }
+        if delta1 != digits_for(&q, b1) {
+            return Err(DecodeError::Dimension { what: "delta1", expected: digits_for(&q, b1), got: delta1 });
+        }
+        let A = MatrixRq::read(rd, "A", n, dim(delta0, m)?)?;
+        let b_rows = rd.get_u32()? as usize;
+        let b_cols = rd.get_u32()? as usize;
+        if b_rows != n { return Err(DecodeError::Dimension { what: "B", expected: n, got: b_rows }); }
+        let b_len = dim(n, b_cols)?;
+        rd.check_fits(b_len, 4 * D)?;
+        let b_data: Vec<Poly> = (0..b_len).map(|_| rd.get_poly()).collect::<Result<_, _>>()?;
+        let mu = rd.get_u32()? as usize;
+        let E = if rd.get_bool("E flag")? { Some(MatrixRq::read(rd, "E", n, mu)?) } else { None };
+        if E.is_none() && mu != 0 { return Err(DecodeError::Invalid("mu without E")); }
+        let b_seed = if rd.get_bool("b_seed flag")? { Some(rd.get_u64()?) } else { None };
+        let r_bound_sq = rd.get_u128()?;
+        if r_bound_sq != (mu * D) as u128 { return Err(DecodeError::Invalid("r_bound_sq is not μ·d")); }
+        let ntiers = rd.get_len(12)?;
+        let mut tiers = Vec::with_capacity(ntiers);
+        let mut delta_in = delta1;
+        for _ in 0..ntiers {
+            let (arity, b, delta) = (rd.get_u32()? as usize, rd.get_u32()?, rd.get_u32()? as usize);
+            if arity < 2 || b < 2 { return Err(DecodeError::Invalid("tier arity or base below 2")); }
+            if delta != digits_for(&q, b) {
+                return Err(DecodeError::Dimension { what: "tier delta", expected: digits_for(&q, b), got: delta });
+            }
+            let M = MatrixRq::read(rd, "tier M", n, dim(dim(n, delta_in)?, arity)?)?;
+            tiers.push(TierParams { arity, b, delta, M });
+            delta_in = delta;
+        }
+        let msg_bound = if rd.get_bool("msg_bound flag")? { Some(rd.get_u32()?) } else { None };
+        let expect0 = match msg_bound {
+            Some(beta) if b0 as u64 == 2 * beta as u64 + 1 => 1,
+            Some(_) => return Err(DecodeError::Invalid("b0 is not 2β+1 in short-message mode")),
+            None => digits_for(&q, b0),
+        };
+        if delta0 != expect0 { return Err(DecodeError::Dimension { what: "delta0", expected: expect0, got: delta0 }); }
+
+        let mut pp = Self { q, n, m, r, delta0, delta1, b0, b1, A, B: MatrixRq::zeros(n, 0),
+                            mu, E, b_seed, r_bound_sq, tiers, msg_bound };
+        let b_expect = dim(dim(n, pp.top_delta())?, *pp.level_nodes().last().unwrap())?;
+        if b_cols != b_expect { return Err(DecodeError::Dimension { what: "B", expected: b_expect, got: b_cols }); }
+        pp.B = MatrixRq::new(n, b_cols, b_data).expect("rows·cols entries");
+        // an appendable B is recomputed from its seed, so the seed cannot disagree with it
+        if let Some(seed) = pp.b_seed {
+            if !pp.tiers.is_empty() { return Err(DecodeError::Invalid("b_seed on a commitment tree")); }
+            let expanded = (0..r).fold(MatrixRq::zeros(n, 0), |acc, i| acc.hcat(&b_block(&q, n, delta1, seed, i)));
+            if expanded != pp.B { return Err(DecodeError::Invalid("B does not match b_seed")); }
+        }
+        Ok(pp)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PARAMS_MAGIC);
+        self.write(&mut w);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut rd = Reader::new(bytes, PARAMS_MAGIC, None)?;
+        let pp = Self::read(&mut rd)?;
+        rd.finish()?;
+        Ok(pp)
+    }
+}
+
+#[cfg(feature = "ark-serialize")]
+greyhound_ring::impl_canonical_serialize!(CommitParams, |pp| pp.to_bytes(), |b| CommitParams::from_bytes(b));
 
 #[cfg(test)]
 mod tests {
@@ -204,6 +850,11 @@ mod tests {
         Poly::from_coeffs(c, q)
     }
 
+    // An explicit copy to tamper with (Decommit is not Clone).
+    fn copy_of(dec: &Decommit) -> Decommit {
+        Decommit { s: dec.s.clone(), that: dec.that.clone(), r: dec.r.clone(), tiers: dec.tiers.clone() }
+    }
+
     #[test]
     fn commit_open_roundtrip() {
         // Tiny, toy parameters (for speed). Dimensions follow Fig. 4 shapes.  :contentReference[oaicite:6]{index=6}
@@ -225,15 +876,250 @@ mod tests {
             f_cols.push(col);
         }
 
-        let Commitment { u, dec } = commit(&pp, &f_cols);
-        assert!(open_check(&pp, &u, &f_cols, &dec));
+        let Commitment { u, dec } = commit(&pp, &f_cols).unwrap();
+        assert_eq!(open_check(&pp, &u, &f_cols, &dec), Ok(()));
+
+        // malformed inputs are errors, not panics
+        assert_eq!(commit(&pp, &f_cols[..1]).err(), Some(OpenError::Dimension { what: "f", index: None, expected: r, got: 1 }));
+        let short = vec![f_cols[0].clone(), f_cols[1][..1].to_vec()];
+        assert_eq!(commit(&pp, &short).err(), Some(OpenError::Dimension { what: "f", index: Some(1), expected: m, got: 1 }));
+        assert_eq!(commit_hiding(&pp, &f_cols, &mut StdRng::seed_from_u64(0)).err(), Some(OpenError::HidingMismatch));
+        assert_eq!(append(&pp, &u, &f_cols[1..]).err(), Some(OpenError::NotAppendable));
+        assert_eq!(pp.clone().grow(r + 1), Err(OpenError::NotAppendable));
+        assert!(MatrixRq::new(2, 2, vec![Poly::zero(); 3]).is_err());
+    }
+
+    #[test]
+    fn tree_commit_open() {
+        let q = ModQ::new(229);
+        let (n, m, r, b0, b1) = (2usize, 3usize, 9usize, 6u32, 7u32);
+        let mut rng = StdRng::seed_from_u64(12);
+        let f_cols: Vec<PolyVec> = (0..r)
+            .map(|_| (0..m).map(|_| rand_poly(&q, &mut rng)).collect())
+            .collect();
+
+        // depth 2 is the classic scheme
+        let pp2 = CommitParams::gen(q, n, m, r, b0, b1, 42).with_depth(2, 42).unwrap();
+        assert_eq!(CommitParams::gen(q, n, m, r, b0, b1, 42).with_depth(1, 42).err(), Some(OpenError::Depth { depth: 1 }));
+        assert_eq!(commit(&pp2, &f_cols).unwrap().u, commit(&CommitParams::gen(q, n, m, r, b0, b1, 42), &f_cols).unwrap().u);
+
+        for depth in [3usize, 4] {
+            let pp = CommitParams::gen(q, n, m, r, b0, b1, 42).with_depth(depth, 42).unwrap();
+            assert_eq!(pp.depth(), depth);
+            let Commitment { u, dec } = commit(&pp, &f_cols).unwrap();
+            assert_eq!(dec.tiers.len(), depth - 2);
+            assert_eq!(dec.tiers.iter().map(|v| v.len()).sum::<usize>(), pp.tier_witness_len());
+            assert_eq!(open_check(&pp, &u, &f_cols, &dec), Ok(()));
+
+            let mut bad = copy_of(&dec);
+            bad.tiers[0][1].c[0] = q.add(bad.tiers[0][1].c[0], 1);
+            if q.signed(bad.tiers[0][1].c[0]).abs() > 3 { bad.tiers[0][1].c[0] = q.sub(bad.tiers[0][1].c[0], 2); }
+            assert_eq!(open_check(&pp, &u, &f_cols, &bad), Err(OpenError::TierMismatch { tier: 0, node: 0 }));
+        }
+    }
+
+    #[test]
+    fn short_message_commit_open() {
+        let q = ModQ::new(229);
+        let (n, m, r, beta) = (2usize, 3usize, 2usize, 8u32);
+        let mut rng = StdRng::seed_from_u64(13);
+        let pp = CommitParams::gen(q, n, m, r, 6, 7, 42).with_short_messages(beta, 1).unwrap();
+        assert_eq!((pp.A.cols, pp.delta0), (m, 1));
+
+        // coefficients in [-β, β]
+        let f_cols: Vec<PolyVec> = (0..r).map(|_| (0..m).map(|_| {
+            let c = std::array::from_fn(|_| (rng.gen_range(-(beta as i64)..=beta as i64).rem_euclid(q.q as i64)) as u32);
+            Poly { c }
+        }).collect()).collect();
+        let Commitment { u, dec } = commit(&pp, &f_cols).unwrap();
+        assert_eq!(dec.s, f_cols);
+        assert_eq!(open_check(&pp, &u, &f_cols, &dec), Ok(()));
+
+        // an opening whose message is long breaks the declared bound
+        let mut f_bad = f_cols.clone();
+        f_bad[0][0].c[0] = 20;
+        let mut bad = copy_of(&dec);
+        bad.s[0][0].c[0] = 20;
+        match open_check(&pp, &u, &f_bad, &bad) {
+            Err(OpenError::DigitBound { part: Part::S(0), index: 0, coeff: 0, bound: 8, .. }) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn short_message_commit_rejects_long_message() {
+        let q = ModQ::new(229);
+        let pp = CommitParams::gen(q, 2, 1, 1, 6, 7, 42).with_short_messages(8, 1).unwrap();
+        assert_eq!(commit(&pp, &[vec![Poly::monomial(3, 9, &q)]]).err(),
+                   Some(OpenError::DigitBound { part: Part::S(0), index: 0, coeff: 3, value: 9, bound: 8 }));
+        assert_eq!(CommitParams::gen(q, 2, 1, 1, 6, 7, 42).with_short_messages(1 << 31, 1).err(),
+                   Some(OpenError::MessageBound { beta: 1 << 31 }));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn decommit_zeroize_wipes_buffers() {
+        use zeroize::{Zeroize, ZeroizeOnDrop};
+        fn wiped_on_drop<T: ZeroizeOnDrop>() {}
+        wiped_on_drop::<Decommit>();
+
+        let q = ModQ::new(229);
+        let mut rng = StdRng::seed_from_u64(15);
+        let pp = CommitParams::gen(q, 2, 3, 2, 6, 7, 42).with_hiding(2, 9);
+        let f_cols: Vec<PolyVec> = (0..2).map(|_| (0..3).map(|_| rand_poly(&q, &mut rng)).collect()).collect();
+        let Commitment { dec, .. } = commit_hiding(&pp, &f_cols, &mut rng).unwrap();
+        let mut dec = dec;
+
+        // Buffers stay allocated after zeroize(), so their old contents can be inspected.
+        let (that_ptr, that_len) = (dec.that.as_ptr(), dec.that.len());
+        let r = dec.r.as_mut().unwrap();
+        let (r_ptr, r_len) = (r.as_ptr(), r.len());
+        assert!(dec.that.iter().any(|p| !p.is_zero()));
+        r.zeroize();
+        dec.that.zeroize();
+        let that_mem = unsafe { std::slice::from_raw_parts(that_ptr, that_len) };
+        let r_mem = unsafe { std::slice::from_raw_parts(r_ptr, r_len) };
+        assert!(that_mem.iter().chain(r_mem).all(|p| p.is_zero()));
+
+        dec.zeroize();
+        assert!(dec.s.is_empty() && dec.that.is_empty() && dec.r.is_none() && dec.tiers.is_empty());
+    }
+
+    #[test]
+    fn open_rejects_out_of_bound_digits() {
+        let q = ModQ::new(229);
+        let (n, m, r, b0, b1) = (2usize, 3usize, 2usize, 6u32, 7u32);
+        let mut rng = StdRng::seed_from_u64(3);
+        let pp = CommitParams::gen(q, n, m, r, b0, b1, 42);
+        let f_cols: Vec<PolyVec> = (0..r)
+            .map(|_| (0..m).map(|_| rand_poly(&q, &mut rng)).collect())
+            .collect();
+        let Commitment { u, dec } = commit(&pp, &f_cols).unwrap();
+
+        // Same f, but 2·b0 moved from digit 1 into digit 0 of s_1 (a non-binding opening).
+        let mut bad = copy_of(&dec);
+        bad.s[1][0].c[5] = q.add(bad.s[1][0].c[5], 2 * b0);
+        bad.s[1][1].c[5] = q.sub(bad.s[1][1].c[5], 2);
+        assert_eq!(greyhound_gadget::g_fwd_vec(&bad.s[1], m, b0, &q), f_cols[1]);
+        match open_check(&pp, &u, &f_cols, &bad) {
+            Err(OpenError::DigitBound { part: Part::S(1), index: 0, coeff: 5, bound: 3, .. }) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let mut bad = copy_of(&dec);
+        bad.that[4].c[0] = 100;
+        match open_check(&pp, &u, &f_cols, &bad) {
+            Err(OpenError::DigitBound { part: Part::That, index: 4, coeff: 0, value: 100, bound: 3 }) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn hiding_open_checks_randomness_norm() {
+        let q = ModQ::new(229);
+        let (n, m, r) = (2usize, 3usize, 2usize);
+        let mut rng = StdRng::seed_from_u64(4);
+        let pp = CommitParams::gen(q, n, m, r, 6, 7, 42).with_hiding(2, 9);
+        let f_cols: Vec<PolyVec> = (0..r)
+            .map(|_| (0..m).map(|_| rand_poly(&q, &mut rng)).collect())
+            .collect();
+        let Commitment { u, dec } = commit_hiding(&pp, &f_cols, &mut rng).unwrap();
+        assert_eq!(open_check_hiding(&pp, &u, &f_cols, &dec), Ok(()));
+
+        // A long r: still opens algebraically if u is recomputed, but fails the ℓ2 bound.
+        let mut bad = copy_of(&dec);
+        let r_bad: PolyVec = (0..2).map(|_| rand_poly(&q, &mut rng)).collect();
+        let mut u_bad = pp.B.mul_vec(&bad.that, &q);
+        let e_r = pp.E.as_ref().unwrap().mul_vec(&r_bad, &q);
+        for i in 0..n { u_bad[i] = u_bad[i].add(&e_r[i], &q); }
+        bad.r = Some(r_bad);
+        match open_check_hiding(&pp, &u_bad, &f_cols, &bad) {
+            Err(OpenError::RandomnessNorm { bound_sq, .. }) => assert_eq!(bound_sq, 128),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(open_check(&pp, &u, &f_cols, &dec), Err(OpenError::HidingMismatch));
+    }
+
+    #[test]
+    fn append_matches_fresh_commit() {
+        let q = ModQ::new(229);
+        let (n, m, b0, b1) = (2usize, 3usize, 6u32, 7u32);
+        let mut rng = StdRng::seed_from_u64(11);
+        let f_cols: Vec<PolyVec> = (0..3)
+            .map(|_| (0..m).map(|_| rand_poly(&q, &mut rng)).collect())
+            .collect();
+
+        // version 2, then append a third column
+        let mut pp = CommitParams::gen_appendable(q, n, m, 2, b0, b1, 5);
+        let Commitment { u: u2, mut dec } = commit(&pp, &f_cols[..2]).unwrap();
+        pp.grow(3).unwrap();
+        let Commitment { u: u3, dec: more } = append(&pp, &u2, &f_cols[2..]).unwrap();
+        dec.extend(more);
+
+        let fresh = commit(&CommitParams::gen_appendable(q, n, m, 3, b0, b1, 5), &f_cols).unwrap();
+        assert_eq!(u3, fresh.u);
+        assert_eq!(open_check(&pp, &u3, &f_cols, &dec), Ok(()));
+
+        // the old version still opens against its own params
+        let pp2 = pp.at_version(2).unwrap();
+        assert_eq!(pp.at_version(4).err(), Some(OpenError::Version { requested: 4, latest: 3 }));
+        let dec2 = Decommit { s: dec.s[..2].to_vec(), that: dec.that[..2 * n * pp.delta1].to_vec(), r: None, tiers: Vec::new() };
+        assert_eq!(open_check(&pp2, &u2, &f_cols[..2], &dec2), Ok(()));
+    }
+
+    #[test]
+    fn prepared_products_match() {
+        let q = ModQ::new(229);
+        let mut rng = StdRng::seed_from_u64(12);
+        let pp = CommitParams::gen(q, 2, 3, 9, 6, 7, 1).with_depth(3, 1).unwrap();
+        let m = MatrixRq::random(3, 4, &q, &mut rng);
+        let x: PolyVec = (0..4).map(|_| rand_poly(&q, &mut rng)).collect();
+        assert_eq!(PreparedMatrix::new(&m, &q).mul_vec(&x, &q), m.mul_vec(&x, &q));
+
+        let f_cols: Vec<PolyVec> = (0..pp.r).map(|_| (0..pp.m).map(|_| rand_poly(&q, &mut rng)).collect()).collect();
+        let prep = Prepared::new(&pp);
+        let (a, b) = (commit_prepared(&pp, &prep, &f_cols).unwrap(), commit(&pp, &f_cols).unwrap());
+        assert_eq!((a.u, &a.dec.that, &a.dec.tiers), (b.u, &b.dec.that, &b.dec.tiers));
+        // a depth far beyond log r still gives arity 2, not an overflow
+        assert_eq!(tier_arity(9, 1 << 20), 2);
+    }
+
+    #[test]
+    fn params_wire_roundtrip() {
+        let q = ModQ::new(229);
+        for pp in [
+            CommitParams::gen(q, 2, 3, 9, 6, 7, 1).with_depth(3, 1).unwrap().with_hiding(2, 5),
+            CommitParams::gen_appendable(q, 2, 3, 4, 6, 7, 2).with_short_messages(8, 3).unwrap(),
+        ] {
+            let bytes = pp.to_bytes();
+            let back = CommitParams::from_bytes(&bytes).unwrap();
+            assert_eq!(back.to_bytes(), bytes);
+            assert_eq!(back.level_nodes(), pp.level_nodes());
+            assert_eq!(CommitParams::from_bytes(&bytes[..bytes.len() - 4]).err(), Some(DecodeError::Truncated));
+        }
+        // δ1 inconsistent with b1
+        let mut bytes = CommitParams::gen(q, 2, 3, 2, 6, 7, 4).to_bytes();
+        bytes[25..29].copy_from_slice(&9u32.to_le_bytes());
+        assert!(matches!(CommitParams::from_bytes(&bytes), Err(DecodeError::Dimension { what: "delta1", .. })));
+        // r_bound_sq other than μ·d, and a b_seed that does not expand to B
+        let mut pp = CommitParams::gen(q, 2, 3, 2, 6, 7, 4).with_hiding(2, 5);
+        pp.r_bound_sq += 1;
+        assert_eq!(CommitParams::from_bytes(&pp.to_bytes()).err(), Some(DecodeError::Invalid("r_bound_sq is not μ·d")));
+        let mut pp = CommitParams::gen_appendable(q, 2, 3, 4, 6, 7, 2);
+        pp.b_seed = Some(3);
+        assert_eq!(CommitParams::from_bytes(&pp.to_bytes()).err(), Some(DecodeError::Invalid("B does not match b_seed")));
+        // a huge declared matrix is rejected against the input length before allocating
+        let big = u32::MAX as usize;
+        let mut w = Writer::new(MATRIX_MAGIC);
+        w.put_u32(u32::MAX).put_u32(u32::MAX);
+        assert_eq!(MatrixRq::from_bytes(&w.finish(), &q, big, big).err(), Some(DecodeError::Truncated));
     }
 }
 
 // crates/commit/src/lib.rs
 impl MatrixRq {
     pub fn zeros(rows: usize, cols: usize) -> Self {
-        Self { rows, cols, data: vec![Poly::zero(); rows*cols] }
+        Self::new(rows, cols, vec![Poly::zero(); rows*cols]).expect("rows·cols entries")
     }
     pub fn set(&mut self, r: usize, c: usize, val: Poly) {
         self.data[r * self.cols + c] = val;
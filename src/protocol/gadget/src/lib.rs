@@ -19,14 +19,6 @@ pub fn digits_for(q: &ModQ, base: u32) -> usize {
     delta
 }
 
-/// Convert a residue in [0,q) to its signed representative in [-(q-1)/2, (q-1)/2].
-#[inline]
-fn signed_rep(x: u32, q: &ModQ) -> i64 {
-    let q64 = q.q as i64;
-    let xi  = x as i64;
-    if xi > q64 / 2 { xi - q64 } else { xi }
-}
-
 /// Map a signed integer back to [0,q) (canonical residue).
 #[inline]
 fn canon_mod_q(x: i64, q: &ModQ) -> u32 {
@@ -55,7 +47,7 @@ fn decompose_coeff_balanced(x: u32, base: u32, delta: usize, q: &ModQ) -> [u32;
     assert!(delta <= 32, "delta too large for fixed buffer; bump if needed");
     let mut out = [0u32; 32];
 
-    let mut y = signed_rep(x, q) as i64;     // work with a small signed integer
+    let mut y = q.signed(x);     // work with a small signed integer
     let b = base as i64;
     let half = (base / 2) as i64;            // floor(b/2)
 
@@ -80,7 +72,7 @@ fn recompose_coeff(digits: &[u32], base: u32, q: &ModQ) -> u32 {
     let mut pow: i128 = 1;                  // b^0
     let b = base as i128;
     for &d in digits {
-        let ds = signed_rep(d, q) as i128;  // interpret balanced digit
+        let ds = q.signed(d) as i128;  // interpret balanced digit
         acc += ds * pow;
         pow *= b;
     }
@@ -157,7 +149,7 @@ mod tests {
             // Bound check: |digit| <= floor(b/2)
             let half = (base / 2) as i64;
             for i in 0..delta {
-                let si = signed_rep(digs[i], &q);
+                let si = q.signed(digs[i]);
                 assert!(si.abs() <= half);
             }
         }
@@ -180,7 +172,7 @@ mod tests {
         let half = (base / 2) as i64;
         for d in ds {
             for &coeff in &d.c {
-                let s = signed_rep(coeff, &q).abs();
+                let s = q.signed(coeff).abs();
                 assert!(s <= half);
             }
         }
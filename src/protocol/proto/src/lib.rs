@@ -1,24 +1,27 @@
-//! Eq. (3) builder + helpers: compute w, \hat w, v; build P,h; FS challenges.
+//! Eq. (3) builder + helpers: compute w, \hat w, v; build P,h; FS challenges;
+//! LaBRADOR proof for the resulting R1 relation P·Z = h, ‖Z‖ ≤ β; exact ℓ2 norm proof.
 
-use greyhound_ring::{ModQ, Poly, D};
-use greyhound_gadget::{g_inv_vec};
-use greyhound_commit::{MatrixRq, CommitParams, PolyVec};
+use greyhound_ring::{DecodeError, Ext4, Fq4, ModQ, Poly, Reader, SecretPolyVec, Writer, D};
+use greyhound_gadget::{digits_for, g_inv_vec};
+use greyhound_commit::{expand_block, MatrixRq, CommitParams, PolyVec};
 use greyhound_transcript::Fs;
-use rand::{SeedableRng, rngs::StdRng};
+use rand::{Rng, rngs::StdRng};
+use std::borrow::Cow;
 
 #[derive(Clone)]
 pub struct ProtoParams<'a> {
     pub commit: &'a CommitParams, // A,B,b0,b1,δ0,δ1,n,m,r,q
-    pub D: MatrixRq,              // n x (δ1*r)
+    pub D: &'a MatrixRq,          // n x (δ1*r)
 }
 
 // === Add near your other structs ===
 pub struct HvzkBuilders<'a> {
     pub pp: &'a CommitParams,  // A,B etc.
-    pub D0: MatrixRq,          // n x (δ1 r)
-    pub D1: MatrixRq,          // n x (δ1 L)
-    pub E0: MatrixRq,          // n x μv
+    pub D0: &'a MatrixRq,      // n x (δ1 r)
+    pub D1: &'a MatrixRq,      // n x (δ1 L)
+    pub E0: &'a MatrixRq,      // n x μv
     pub L: usize,
+    pub z_mask: Option<ZMask>, // z = y + S c (None: z = S c)
 }
 
 pub struct HvzkPublic<'a> {
@@ -29,127 +32,922 @@ pub struct HvzkPublic<'a> {
     pub j: Vec<Poly>,             // L ring polys (ct(j_i) will be checked outside)
     pub alpha: Vec<u32>,          // L field scalars
     pub sigma_inv_x: Poly,        // σ^{-1}(x)
+    pub c: &'a [Poly],            // amortization challenge (r ring polys)
+    pub z_msg: Option<&'a ZMaskMsg>, // t_y, w_y when z is masked
 }
 
-// === Complete Eq.(14): columns are [what | lhat | rv | that | r | z] ===
-pub fn build_eq14(
-    B: &HvzkBuilders,
+// === Block-sparse P: references to A, B, D, … and implicit gadget blocks ===
+
+/// One block of a `SparseP`, placed at some (row, col) offset.
+#[derive(Clone, Debug)]
+pub enum PBlock<'a> {
+    /// ±M restricted to its first `cols` columns.
+    Mat { m: Cow<'a, MatrixRq>, cols: usize, neg: bool },
+    /// A single explicit row.
+    Row(PolyVec),
+    /// A single row v^T G_{b,|v|} (length δ|v|), as `row_vec_times_G`.
+    GadgetRow { v: PolyVec, base: u32, delta: usize },
+    /// ±(c^T ⊗ G_{b,n}): n rows, n·δ·|c| columns, as `cotimes_G_block`.
+    CotimesG { c: PolyVec, n: usize, base: u32, delta: usize, neg: bool },
+    /// −G_{b,n}: n rows, n·δ columns.
+    NegG { n: usize, base: u32, delta: usize },
+}
+
+fn gadget_pows(base: u32, delta: usize, q: &ModQ) -> Vec<u32> {
+    let mut pow = vec![1u32 % q.q; delta];
+    for k in 1..delta { pow[k] = q.mul(pow[k - 1], base); }
+    pow
+}
+
+fn scale(p: &Poly, k: u32, q: &ModQ) -> Poly {
+    let mut out = Poly::zero();
+    for t in 0..D { out.c[t] = q.mul(p.c[t], k); }
+    out
+}
+
+// Σ_k b^k x_k
+fn recompose(x: &[Poly], pow: &[u32], q: &ModQ) -> Poly {
+    x.iter().zip(pow).fold(Poly::zero(), |acc, (xk, &p)| acc.add(&scale(xk, p, q), q))
+}
+
+impl PBlock<'_> {
+    pub fn mat(m: &MatrixRq) -> PBlock<'_> { PBlock::Mat { m: Cow::Borrowed(m), cols: m.cols, neg: false } }
+    pub fn neg_mat(m: &MatrixRq) -> PBlock<'_> { PBlock::Mat { m: Cow::Borrowed(m), cols: m.cols, neg: true } }
+
+    pub fn rows(&self) -> usize {
+        match self {
+            PBlock::Mat { m, .. } => m.rows,
+            PBlock::Row(_) | PBlock::GadgetRow { .. } => 1,
+            PBlock::CotimesG { n, .. } | PBlock::NegG { n, .. } => *n,
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        match self {
+            PBlock::Mat { cols, .. } => *cols,
+            PBlock::Row(v) => v.len(),
+            PBlock::GadgetRow { v, delta, .. } => v.len() * delta,
+            PBlock::CotimesG { c, n, delta, .. } => c.len() * n * delta,
+            PBlock::NegG { n, delta, .. } => n * delta,
+        }
+    }
+
+    /// Ring elements the block stores (gadget entries are scalars and not counted):
+    /// what one verifier pass over the block evaluates.
+    pub fn ring_entries(&self) -> usize {
+        match self {
+            PBlock::Mat { m, cols, .. } => m.rows * cols,
+            PBlock::Row(v) | PBlock::GadgetRow { v, .. } => v.len(),
+            PBlock::CotimesG { c, .. } => c.len(),
+            PBlock::NegG { .. } => 0,
+        }
+    }
+
+    // out += block · x
+    fn mul_acc(&self, x: &[Poly], out: &mut [Poly], q: &ModQ) {
+        match self {
+            PBlock::Mat { m, cols, neg } => {
+                for (i, o) in out[..m.rows].iter_mut().enumerate() {
+                    let acc = x[..*cols].iter().enumerate().fold(Poly::zero(), |acc, (c, xc)| acc.add(&m.at(i, c).mul(xc, q), q));
+                    *o = if *neg { o.sub(&acc, q) } else { o.add(&acc, q) };
+                }
+            }
+            PBlock::Row(v) => {
+                for (vk, xk) in v.iter().zip(x) { out[0] = out[0].add(&vk.mul(xk, q), q); }
+            }
+            PBlock::GadgetRow { v, base, delta } => {
+                let pow = gadget_pows(*base, *delta, q);
+                for (i, vi) in v.iter().enumerate() {
+                    let g = recompose(&x[i * delta..(i + 1) * delta], &pow, q);
+                    out[0] = out[0].add(&vi.mul(&g, q), q);
+                }
+            }
+            PBlock::CotimesG { c, n, base, delta, neg } => {
+                let pow = gadget_pows(*base, *delta, q);
+                for (i, ci) in c.iter().enumerate() {
+                    for (row, o) in out[..*n].iter_mut().enumerate() {
+                        let k0 = i * n * delta + row * delta;
+                        let g = ci.mul(&recompose(&x[k0..k0 + delta], &pow, q), q);
+                        *o = if *neg { o.sub(&g, q) } else { o.add(&g, q) };
+                    }
+                }
+            }
+            PBlock::NegG { n, base, delta } => {
+                let pow = gadget_pows(*base, *delta, q);
+                for (o, xr) in out[..*n].iter_mut().zip(x.chunks(*delta)) {
+                    *o = o.sub(&recompose(xr, &pow, q), q);
+                }
+            }
+        }
+    }
+
+    // out += block^T · y
+    fn mul_transpose_acc(&self, y: &[Poly], out: &mut [Poly], q: &ModQ) {
+        match self {
+            PBlock::Mat { m, cols, neg } => {
+                for (c, o) in out[..*cols].iter_mut().enumerate() {
+                    let acc = y[..m.rows].iter().enumerate().fold(Poly::zero(), |acc, (i, yi)| acc.add(&m.at(i, c).mul(yi, q), q));
+                    *o = if *neg { o.sub(&acc, q) } else { o.add(&acc, q) };
+                }
+            }
+            PBlock::Row(v) => {
+                for (k, vk) in v.iter().enumerate() { out[k] = out[k].add(&vk.mul(&y[0], q), q); }
+            }
+            PBlock::GadgetRow { v, base, delta } => {
+                let pow = gadget_pows(*base, *delta, q);
+                for (i, vi) in v.iter().enumerate() {
+                    let vy = vi.mul(&y[0], q);
+                    for (o, &pk) in out[i * delta..(i + 1) * delta].iter_mut().zip(&pow) { *o = o.add(&scale(&vy, pk, q), q); }
+                }
+            }
+            PBlock::CotimesG { c, n, base, delta, neg } => {
+                let pow = gadget_pows(*base, *delta, q);
+                for (i, ci) in c.iter().enumerate() {
+                    for (row, yr) in y[..*n].iter().enumerate() {
+                        let cy = ci.mul(yr, q);
+                        let cy = if *neg { cy.neg(q) } else { cy };
+                        let col = i * n * delta + row * delta;
+                        for (o, &pk) in out[col..col + delta].iter_mut().zip(&pow) { *o = o.add(&scale(&cy, pk, q), q); }
+                    }
+                }
+            }
+            PBlock::NegG { n, base, delta } => {
+                let pow = gadget_pows(*base, *delta, q);
+                for (yr, out_r) in y[..*n].iter().zip(out.chunks_mut(*delta)) {
+                    for (o, &pk) in out_r.iter_mut().zip(&pow) { *o = o.sub(&scale(yr, pk, q), q); }
+                }
+            }
+        }
+    }
+
+    // out_k += Σ_i w_i · block_ik(α); each stored entry is evaluated once, gadget
+    // entries are the scalars ±b^t.
+    fn fold_rows_ext_acc(&self, w: &[Fq4], alpha: &Fq4, e: &Ext4, out: &mut [Fq4]) {
+        let q = e.q;
+        let signed = |x: Fq4, neg: bool| if neg { e.neg(&x) } else { x };
+        match self {
+            PBlock::Mat { m, cols, neg } => {
+                for (c, o) in out[..*cols].iter_mut().enumerate() {
+                    let acc = (0..m.rows).fold(Fq4::zero(), |acc, i| e.add(&acc, &e.mul(&w[i], &m.at(i, c).eval_ext(alpha, e))));
+                    *o = e.add(o, &signed(acc, *neg));
+                }
+            }
+            PBlock::Row(v) => {
+                for (k, vk) in v.iter().enumerate() { out[k] = e.add(&out[k], &e.mul(&w[0], &vk.eval_ext(alpha, e))); }
+            }
+            PBlock::GadgetRow { v, base, delta } => {
+                let pow = gadget_pows(*base, *delta, &q);
+                for (i, vi) in v.iter().enumerate() {
+                    let wv = e.mul(&w[0], &vi.eval_ext(alpha, e));
+                    for (o, &pk) in out[i * delta..(i + 1) * delta].iter_mut().zip(&pow) { *o = e.add(o, &e.scale(&wv, pk)); }
+                }
+            }
+            PBlock::CotimesG { c, n, base, delta, neg } => {
+                let pow = gadget_pows(*base, *delta, &q);
+                for (i, ci) in c.iter().enumerate() {
+                    let ca = signed(ci.eval_ext(alpha, e), *neg);
+                    for (row, wr) in w[..*n].iter().enumerate() {
+                        let wc = e.mul(wr, &ca);
+                        let col = i * n * delta + row * delta;
+                        for (o, &pk) in out[col..col + delta].iter_mut().zip(&pow) { *o = e.add(o, &e.scale(&wc, pk)); }
+                    }
+                }
+            }
+            PBlock::NegG { n, base, delta } => {
+                let pow = gadget_pows(*base, *delta, &q);
+                for (wr, out_r) in w[..*n].iter().zip(out.chunks_mut(*delta)) {
+                    for (o, &pk) in out_r.iter_mut().zip(&pow) { *o = e.sub(o, &e.scale(wr, pk)); }
+                }
+            }
+        }
+    }
+
+    fn absorb(&self, fs: &mut Fs) {
+        match self {
+            PBlock::Mat { m, cols, neg } => {
+                fs.absorb_u64(0).absorb_u64(m.rows as u64).absorb_u64(*cols as u64).absorb_u64(*neg as u64);
+                for i in 0..m.rows { fs.absorb_polyvec(&m.data[i * m.cols..i * m.cols + cols]); }
+            }
+            PBlock::Row(v) => { fs.absorb_u64(1).absorb_polyvec(v); }
+            PBlock::GadgetRow { v, base, delta } => {
+                fs.absorb_u64(2).absorb_u64(*base as u64).absorb_u64(*delta as u64).absorb_polyvec(v);
+            }
+            PBlock::CotimesG { c, n, base, delta, neg } => {
+                fs.absorb_u64(3).absorb_u64(*n as u64).absorb_u64(*base as u64).absorb_u64(*delta as u64)
+                  .absorb_u64(*neg as u64).absorb_polyvec(c);
+            }
+            PBlock::NegG { n, base, delta } => {
+                fs.absorb_u64(4).absorb_u64(*n as u64).absorb_u64(*base as u64).absorb_u64(*delta as u64);
+            }
+        }
+    }
+
+    /// The `absorb` tag, then the same fields; `Mat` keeps only its first `cols` columns.
+    fn write(&self, w: &mut Writer) {
+        match self {
+            PBlock::Mat { m, cols, neg } => {
+                w.put_u8(0).put_u8(*neg as u8).put_len(m.rows).put_len(*cols).put_len(m.rows * cols);
+                for i in 0..m.rows { for p in &m.data[i * m.cols..i * m.cols + cols] { w.put_poly(p); } }
+            }
+            PBlock::Row(v) => { w.put_u8(1).put_polys(v); }
+            PBlock::GadgetRow { v, base, delta } => { w.put_u8(2).put_u32(*base).put_len(*delta).put_polys(v); }
+            PBlock::CotimesG { c, n, base, delta, neg } => {
+                w.put_u8(3).put_len(*n).put_u32(*base).put_len(*delta).put_u8(*neg as u8).put_polys(c);
+            }
+            PBlock::NegG { n, base, delta } => { w.put_u8(4).put_len(*n).put_u32(*base).put_len(*delta); }
+        }
+    }
+
+    /// Reads a block whose (rows, cols) are checked not to overflow.
+    fn read(rd: &mut Reader) -> Result<PBlock<'static>, DecodeError> {
+        let overflow = DecodeError::Invalid("block of P overflows");
+        let block = match rd.get_u8()? {
+            0 => {
+                let neg = rd.get_bool("Mat sign")?;
+                let (rows, cols) = (rd.get_u32()? as usize, rd.get_u32()? as usize);
+                let data = rd.get_polys_exact("Mat block", rows.checked_mul(cols).ok_or(overflow.clone())?)?;
+                PBlock::Mat { m: Cow::Owned(MatrixRq::new(rows, cols, data).expect("length checked")), cols, neg }
+            }
+            1 => PBlock::Row(rd.get_polys()?),
+            2 => {
+                let (base, delta) = (rd.get_u32()?, rd.get_u32()? as usize);
+                let v = rd.get_polys()?;
+                v.len().checked_mul(delta).ok_or(overflow.clone())?;
+                PBlock::GadgetRow { v, base, delta }
+            }
+            3 => {
+                let (n, base, delta) = (rd.get_u32()? as usize, rd.get_u32()?, rd.get_u32()? as usize);
+                let neg = rd.get_bool("CotimesG sign")?;
+                let c = rd.get_polys()?;
+                c.len().checked_mul(n).and_then(|x| x.checked_mul(delta)).ok_or(overflow.clone())?;
+                PBlock::CotimesG { c, n, base, delta, neg }
+            }
+            4 => {
+                let (n, base, delta) = (rd.get_u32()? as usize, rd.get_u32()?, rd.get_u32()? as usize);
+                n.checked_mul(delta).ok_or(overflow)?;
+                PBlock::NegG { n, base, delta }
+            }
+            tag => return Err(DecodeError::Tag { what: "block of P", tag }),
+        };
+        Ok(block)
+    }
+}
+
+/// rows x cols matrix over R_q given as a sum of blocks at (row, col) offsets;
+/// never materialized (except by `to_dense`, for debugging).
+#[derive(Clone, Debug)]
+pub struct SparseP<'a> {
+    pub rows: usize,
+    pub cols: usize,
+    blocks: Vec<(usize, usize, PBlock<'a>)>,
+}
+
+impl<'a> SparseP<'a> {
+    pub fn new(rows: usize, cols: usize) -> Self { Self { rows, cols, blocks: Vec::new() } }
+
+    /// Add `block` at (row, col); it must fit inside P.
+    pub fn push(&mut self, row: usize, col: usize, block: PBlock<'a>) -> Result<(), R1Error> {
+        let fits = |at: usize, len: usize, max: usize| at.checked_add(len).is_some_and(|end| end <= max);
+        if !fits(row, block.rows(), self.rows) || !fits(col, block.cols(), self.cols) {
+            return Err(R1Error::Block { row, col });
+        }
+        self.blocks.push((row, col, block));
+        Ok(())
+    }
+
+    pub fn blocks(&self) -> &[(usize, usize, PBlock<'a>)] { &self.blocks }
+    /// Σ `PBlock::ring_entries`: the ring elements a verifier evaluates to check P·Z = h.
+    pub fn ring_entries(&self) -> usize { self.blocks.iter().map(|(_, _, b)| b.ring_entries()).sum() }
+
+    pub fn mul_vec(&self, x: &PolyVec, q: &ModQ) -> PolyVec {
+        assert_eq!(x.len(), self.cols);
+        let mut out = vec![Poly::zero(); self.rows];
+        for (r, c, b) in &self.blocks {
+            b.mul_acc(&x[*c..*c + b.cols()], &mut out[*r..*r + b.rows()], q);
+        }
+        out
+    }
+
+    pub fn mul_transpose(&self, y: &PolyVec, q: &ModQ) -> PolyVec {
+        assert_eq!(y.len(), self.rows);
+        let mut out = vec![Poly::zero(); self.cols];
+        for (r, c, b) in &self.blocks {
+            b.mul_transpose_acc(&y[*r..*r + b.rows()], &mut out[*c..*c + b.cols()], q);
+        }
+        out
+    }
+
+    /// Σ_i w_i · P_ik(α) for every column k, in F_{q^4}: the row fold a ring-switching
+    /// verifier needs. Costs one evaluation per stored entry of A, B, D, … and O(1) per
+    /// gadget entry, so O(nnz) rather than the rows · nnz of going through `to_dense`.
+    pub fn fold_rows_ext(&self, w: &[Fq4], alpha: &Fq4, e: &Ext4) -> Vec<Fq4> {
+        assert_eq!(w.len(), self.rows);
+        let mut out = vec![Fq4::zero(); self.cols];
+        for (r, c, b) in &self.blocks {
+            b.fold_rows_ext_acc(&w[*r..*r + b.rows()], alpha, e, &mut out[*c..*c + b.cols()]);
+        }
+        out
+    }
+
+    /// Dense copy (rows · cols ring elements).
+    pub fn to_dense(&self, q: &ModQ) -> MatrixRq {
+        let mut data = Vec::with_capacity(self.rows * self.cols);
+        for i in 0..self.rows {
+            let mut e = vec![Poly::zero(); self.rows];
+            e[i] = Poly::monomial(0, 1, q);
+            data.extend(self.mul_transpose(&e, q));
+        }
+        MatrixRq::new(self.rows, self.cols, data).expect("rows·cols entries")
+    }
+
+    pub fn absorb(&self, fs: &mut Fs) {
+        fs.absorb_u64(self.rows as u64).absorb_u64(self.cols as u64).absorb_u64(self.blocks.len() as u64);
+        for (r, c, b) in &self.blocks {
+            fs.absorb_u64(*r as u64).absorb_u64(*c as u64);
+            b.absorb(fs);
+        }
+    }
+}
+
+impl From<MatrixRq> for SparseP<'static> {
+    fn from(m: MatrixRq) -> Self {
+        let mut p = SparseP::new(m.rows, m.cols);
+        let cols = m.cols;
+        p.push(0, 0, PBlock::Mat { m: Cow::Owned(m), cols, neg: false }).expect("m fills P");
+        p
+    }
+}
+
+// === R1: P·Z = h, ‖Z‖₂ ≤ β, with named column blocks ===
+
+/// Column blocks of an R1 witness, in the order they appear in `Layout`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Block {
+    What,     // \hat w = G^{-1}(w)
+    Lhat,     // \hat l (HVZK masks)
+    Rv,       // r_v (randomness of v)
+    That,     // \hat t
+    Tiers,    // upper commitment-tree levels
+    R,        // hiding randomness r
+    Z,        // amortized opening z
+    Garbage,  // \hat h (LaBRADOR garbage terms)
+}
+
+impl Block {
+    pub fn name(self) -> &'static str {
+        match self {
+            Block::What => "what", Block::Lhat => "lhat", Block::Rv => "rv", Block::That => "that",
+            Block::Tiers => "tiers", Block::R => "r", Block::Z => "z", Block::Garbage => "garbage",
+        }
+    }
+
+    const ALL: [Block; 8] = [Block::What, Block::Lhat, Block::Rv, Block::That, Block::Tiers, Block::R, Block::Z, Block::Garbage];
+}
+
+/// Witness column layout: consecutive named blocks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    blocks: Vec<(Block, usize)>,
+}
+
+impl Layout {
+    pub fn new() -> Self { Self::default() }
+
+    /// Append a block of `len` columns; each block appears at most once.
+    pub fn with(mut self, block: Block, len: usize) -> Result<Self, R1Error> {
+        if self.has(block) { return Err(R1Error::DuplicateBlock(block)); }
+        self.blocks.push((block, len));
+        Ok(self)
+    }
+
+    pub fn blocks(&self) -> &[(Block, usize)] { &self.blocks }
+    pub fn cols(&self) -> usize { self.blocks.iter().map(|&(_, len)| len).sum() }
+    pub fn has(&self, block: Block) -> bool { self.blocks.iter().any(|&(b, _)| b == block) }
+
+    /// Columns of `block`.
+    pub fn range(&self, block: Block) -> std::ops::Range<usize> {
+        let mut off = 0;
+        for &(b, len) in &self.blocks {
+            if b == block { return off..off + len; }
+            off += len;
+        }
+        panic!("layout has no {:?} block", block)
+    }
+
+    pub fn offset(&self, block: Block) -> usize { self.range(block).start }
+
+    /// Concatenate witness parts given in layout order, checking each length.
+    pub fn assemble(&self, parts: &[(Block, &[Poly])]) -> Result<PolyVec, R1Error> {
+        if parts.len() != self.blocks.len() {
+            return Err(R1Error::Dimension { what: "layout", expected: self.blocks.len(), got: parts.len() });
+        }
+        let mut Z = Vec::with_capacity(self.cols());
+        for (&(block, len), &(b, part)) in self.blocks.iter().zip(parts) {
+            assert_eq!(block, b, "witness parts out of layout order");
+            if part.len() != len {
+                return Err(R1Error::Dimension { what: block.name(), expected: len, got: part.len() });
+            }
+            Z.extend_from_slice(part);
+        }
+        Ok(Z)
+    }
+}
+
+/// Why a witness does not satisfy an `R1`, or why a serialized one is malformed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum R1Error {
+    /// Wrong length of `what` ("Z", "h", "layout", or a public input such as "v").
+    Dimension { what: &'static str, expected: usize, got: usize },
+    /// Row `row` of P·Z differs from h; `block` names its row block ("u", "y", ...).
+    Row { row: usize, block: &'static str },
+    /// ‖Z‖₂² is above the bound.
+    Norm { norm_sq: u128, bound_sq: u128 },
+    /// `from_bytes` input is not a valid encoding.
+    Decode(DecodeError),
+    /// The commitment params lack a matrix the relation needs (e.g. the hiding E).
+    Params(&'static str),
+    /// Rejection sampling for a masked z did not accept within `attempts` tries.
+    Rejected { attempts: u32 },
+    /// A block of P placed at (row, col) does not fit inside P.
+    Block { row: usize, col: usize },
+    /// The layout already has this column block.
+    DuplicateBlock(Block),
+}
+
+impl std::fmt::Display for R1Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            R1Error::Dimension { what, expected, got } =>
+                write!(f, "dimension mismatch in {}: expected {}, got {}", what, expected, got),
+            R1Error::Row { row, block } => write!(f, "row block {} mismatch (row {})", block, row),
+            R1Error::Norm { norm_sq, bound_sq } => write!(f, "‖Z‖₂² = {} exceeds bound {}", norm_sq, bound_sq),
+            R1Error::Decode(why) => write!(f, "malformed R1 encoding: {}", why),
+            R1Error::Params(what) => write!(f, "commitment params have no {}", what),
+            R1Error::Rejected { attempts } => write!(f, "masked z rejected {} times", attempts),
+            R1Error::Block { row, col } => write!(f, "block of P at ({}, {}) out of range", row, col),
+            R1Error::DuplicateBlock(block) => write!(f, "duplicate {} block in the layout", block.name()),
+        }
+    }
+}
+
+impl std::error::Error for R1Error {}
+
+/// The relation R1 over R_q: P·Z = h and ‖Z‖₂² ≤ beta_sq (centered coefficients),
+/// with Z split into the named blocks of `layout`.
+#[derive(Clone, Debug)]
+pub struct R1<'a> {
+    pub q: ModQ,
+    pub P: SparseP<'a>,
+    pub h: PolyVec,
+    pub beta_sq: u128,
+    pub layout: Layout,
+    /// (first row, name) of each row block, in row order; only used for error reports.
+    pub row_blocks: Vec<(usize, &'static str)>,
+}
+
+const R1_MAGIC: &[u8; 4] = b"GHR1";
+
+impl R1<'_> {
+    /// Name of the row block containing `row` ("P" if the builder named none).
+    pub fn row_block(&self, row: usize) -> &'static str {
+        self.row_blocks.iter().rev().find(|&&(r0, _)| r0 <= row).map_or("P", |&(_, name)| name)
+    }
+
+    pub fn check(&self, Z: &PolyVec) -> Result<(), R1Error> {
+        let q = &self.q;
+        if self.layout.cols() != self.P.cols {
+            return Err(R1Error::Dimension { what: "layout", expected: self.P.cols, got: self.layout.cols() });
+        }
+        if self.h.len() != self.P.rows {
+            return Err(R1Error::Dimension { what: "h", expected: self.P.rows, got: self.h.len() });
+        }
+        if Z.len() != self.P.cols {
+            return Err(R1Error::Dimension { what: "Z", expected: self.P.cols, got: Z.len() });
+        }
+        let lhs = self.P.mul_vec(Z, q);
+        if let Some(row) = (0..lhs.len()).find(|&i| lhs[i] != self.h[i]) {
+            return Err(R1Error::Row { row, block: self.row_block(row) });
+        }
+        let norm_sq = Z.iter().map(|p| p.l2_sq(q)).sum::<u128>();
+        if norm_sq > self.beta_sq {
+            return Err(R1Error::Norm { norm_sq, bound_sq: self.beta_sq });
+        }
+        Ok(())
+    }
+
+    /// Magic and version, q, rows, cols, β², layout, the blocks of P with their
+    /// offsets, h. P keeps its block structure, so a decoded relation absorbs into
+    /// the transcript exactly like the original.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(R1_MAGIC);
+        w.put_u32(self.q.q).put_len(self.P.rows).put_len(self.P.cols).put_u128(self.beta_sq);
+        w.put_len(self.layout.blocks.len());
+        for &(b, len) in &self.layout.blocks {
+            w.put_u8(Block::ALL.iter().position(|&x| x == b).unwrap() as u8).put_len(len);
+        }
+        w.put_len(self.P.blocks.len());
+        for (r, c, b) in &self.P.blocks {
+            w.put_len(*r).put_len(*c);
+            b.write(&mut w);
+        }
+        w.put_polys(&self.h);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<R1<'static>, R1Error> {
+        let mut rd = Reader::new(bytes, R1_MAGIC, None).map_err(R1Error::Decode)?;
+        let r1 = R1::read(&mut rd).map_err(R1Error::Decode)?;
+        rd.finish().map_err(R1Error::Decode)?;
+        Ok(r1)
+    }
+
+    fn read(rd: &mut Reader) -> Result<R1<'static>, DecodeError> {
+        let q = rd.get_modulus()?;
+        let (rows, cols, beta_sq) = (rd.get_u32()? as usize, rd.get_u32()? as usize, rd.get_u128()?);
+        let mut layout = Layout::new();
+        let mut layout_cols = 0usize;
+        for _ in 0..rd.get_len(5)? {
+            let tag = rd.get_u8()?;
+            let block = *Block::ALL.get(tag as usize).ok_or(DecodeError::Tag { what: "layout block", tag })?;
+            let len = rd.get_u32()? as usize;
+            layout_cols = layout_cols.checked_add(len).ok_or(DecodeError::Invalid("layout overflows"))?;
+            layout = layout.with(block, len).map_err(|_| DecodeError::Invalid("duplicate layout block"))?;
+        }
+        if layout_cols != cols {
+            return Err(DecodeError::Dimension { what: "layout", expected: cols, got: layout_cols });
+        }
+        let mut P = SparseP::new(rows, cols);
+        for _ in 0..rd.get_len(9)? {
+            let (r, c) = (rd.get_u32()? as usize, rd.get_u32()? as usize);
+            let block = PBlock::read(rd)?;
+            P.push(r, c, block).map_err(|_| DecodeError::Invalid("block of P out of range"))?;
+        }
+        let h = rd.get_polys_exact("h", rows)?;
+        Ok(R1 { q, P, h, beta_sq, layout, row_blocks: Vec::new() })
+    }
+}
+
+/// Column layout of the Eq. (14) witness, [what | lhat | rv | that | tiers | r | z],
+/// shared by `build_eq14` and both PCS sides.
+pub fn eq14_layout(B: &HvzkBuilders) -> Result<Layout, R1Error> {
+    let pp = B.pp;
+    Layout::new()
+        .with(Block::What, pp.delta1 * pp.r)?
+        .with(Block::Lhat, pp.delta1 * B.L)?
+        .with(Block::Rv, B.E0.cols)?
+        .with(Block::That, pp.n * pp.delta1 * pp.r)?
+        .with(Block::Tiers, pp.tier_witness_len())?
+        .with(Block::R, pp.mu)?
+        .with(Block::Z, pp.delta0 * pp.m)
+}
+
+/// The complete HVZK relation: Eq. (14) rows, then the Eq. (3) amortization rows
+/// c^T G . what − a^T . z = 0 and (c^T ⊗ G_n) . that − A . z = 0.
+pub fn build_eq14<'a>(
+    B: &HvzkBuilders<'a>,
     q: &ModQ,
     pubin: &HvzkPublic,
-) -> (MatrixRq, Vec<Poly>) {
+) -> Result<R1<'a>, R1Error> {
     let pp = B.pp;
     let delta1 = pp.delta1;
     let n = pp.n;
-    let rcols = pp.r;
+    let E = pp.E.as_ref().ok_or(R1Error::Params("hiding matrix E"))?;
+    for (what, expected, got) in [("v", n, pubin.v.len()), ("u", n, pubin.u.len()), ("j", B.L, pubin.j.len()), ("b", pp.r, pubin.b.len()),
+                                  ("alpha", B.L, pubin.alpha.len()), ("c", pp.r, pubin.c.len()), ("a", pp.delta0 * pp.m, pubin.a.len()),
+                                  ("z mask message", B.z_mask.is_some() as usize, pubin.z_msg.is_some() as usize),
+                                  ("t_y", n, pubin.z_msg.map_or(n, |m| m.t_y.len()))] {
+        if got != expected { return Err(R1Error::Dimension { what, expected, got }); }
+    }
 
-    let off_w  = 0;
-    let off_l  = off_w + delta1 * rcols;
-    let off_rv = off_l + delta1 * B.L;
-    let off_t  = off_rv + B.E0.cols;
-    let off_r  = off_t + (n * delta1 * rcols);
-    let off_z  = off_r + pp.mu;
+    let layout = eq14_layout(B)?;
+    let off_w  = layout.offset(Block::What);
+    let off_l  = layout.offset(Block::Lhat);
+    let off_rv = layout.offset(Block::Rv);
+    let off_t  = layout.offset(Block::That);
+    let off_r  = layout.offset(Block::R);
+    let off_z  = layout.offset(Block::Z);
+    let n_outer = outer_rows(pp);
 
     let rows = n       // v rows: [D0 | D1 | E0] * [what|lhat|rv] = v
-             + n       // u rows: [B | E] * [that|r] = u
+             + n_outer // tier rows, then u rows: [B | E] * [that|tiers|r] = u
              + B.L     // L rows: α_i σ^{-1}(x) b^T G . what  +  e_i G_L . lhat = j_i
-             + 1       // c^T G . what  - a^T . z = 0 (we’ll let caller append c, a later if desired)
-             + n;      // (c^T ⊗ G_n) . that  - A . z = 0 (same, caller appends c)
+             + 1       // c^T G . what  - a^T . z = 0
+             + n;      // (c^T ⊗ G_n) . that  - A . z = 0
 
-    let cols = off_z + (pp.delta0 * pp.m);
-    let mut P = MatrixRq::zeros(rows, cols);
+    let mut P = SparseP::new(rows, layout.cols());
     let mut h = Vec::<Poly>::with_capacity(rows);
 
     // Block 1: v = D0 what + D1 lhat + E0 rv
-    for rr in 0..n {
-        for j in 0..(delta1 * rcols) { P.set(rr, off_w + j, B.D0.at(rr, j).clone()); }
-        for j in 0..(delta1 * B.L)   { P.set(rr, off_l + j, B.D1.at(rr, j).clone()); }
-        for j in 0..B.E0.cols        { P.set(rr, off_rv + j, B.E0.at(rr, j).clone()); }
-        h.push(pubin.v[rr].clone());
-    }
+    P.push(0, off_w, PBlock::mat(B.D0))?;
+    P.push(0, off_l, PBlock::mat(B.D1))?;
+    P.push(0, off_rv, PBlock::mat(B.E0))?;
+    h.extend_from_slice(&pubin.v[..n]);
 
-    // Block 2: u = B that + E r
-    for rr in 0..n {
-        let dst = n + rr;
-        for j in 0..(n * delta1 * rcols) { P.set(dst, off_t + j, pp.B.at(rr, j).clone()); }
-        let E = pp.E.as_ref().expect("hiding E");
-        for j in 0..pp.mu { P.set(dst, off_r + j, E.at(rr, j).clone()); }
-        h.push(pubin.u[rr].clone());
-    }
+    // Block 2: tier rows, then u = B top + E r
+    set_outer_block(&mut P, &mut h, n, off_t, pp, pubin.u)?;
+    P.push(n_outer, off_r, PBlock::mat(E))?;
 
-    // Precompute α_i σ^{-1}(x) * b and its G row on what
+    // σ^{-1}(x) * b, whose G row goes on what
     let mut sigb = pubin.b.clone();
     for bi in &mut sigb { *bi = pubin.sigma_inv_x.mul(bi, q); }
-    let sigbG = row_vec_times_G(&sigb, pp.b1, delta1, q);
 
     // Row group: for each i, α_i * sigbG on what  and  e_i * G_L on lhat
-    // Precompute powers b1^j as ring scalars
-    let mut pow = vec![1u32; delta1];
-    for j in 1..delta1 { pow[j] = ((pow[j-1] as u64 * pp.b1 as u64) % (q.q as u64)) as u32; }
-
     for i in 0..B.L {
-        let dst = 2*n + i;
+        let dst = n + n_outer + i;
+        let v = sigb.iter().map(|p| scale(p, pubin.alpha[i], q)).collect();
+        P.push(dst, off_w, PBlock::GadgetRow { v, base: pp.b1, delta: delta1 })?;
+        // e_i * G_{b1,L} on lhat: δ1 digits in the i-th lhat “slot”
+        P.push(dst, off_l + i * delta1, PBlock::GadgetRow { v: vec![Poly::monomial(0, 1, q)], base: pp.b1, delta: delta1 })?;
+        h.push(pubin.j[i].clone());
+    }
 
-        // α_i * sigbG on what
-        let alpha_i = pubin.alpha[i] as u64;
-        for j in 0..sigbG.len() {
-            let mut c = [0u32; D];
-            for t in 0..D { c[t] = ((sigbG[j].c[t] as u64 * alpha_i) % (q.q as u64)) as u32; }
-            P.set(dst, off_w + j, Poly { c });
-        }
+    // c^T G . what  - a^T . z = -w_y  (0 without a mask)
+    let row = n + n_outer + B.L;
+    P.push(row, off_w, PBlock::GadgetRow { v: pubin.c.to_vec(), base: pp.b1, delta: delta1 })?;
+    P.push(row, off_z, PBlock::Row(pubin.a.iter().map(|p| p.neg(q)).collect()))?;
+    h.push(pubin.z_msg.map_or(Poly::zero(), |m| m.w_y.neg(q)));
 
-        // e_i * G_{b1,L} on lhat: place δ1 digits in the i-th lhat “slot”
-        let start = off_l + i*delta1;
-        for j in 0..delta1 {
-            let mut c = [0u32; D];
-            c[0] = pow[j];
-            P.set(dst, start + j, Poly { c });
-        }
-
-        // RHS j_i
-        h.push(pubin.j[i].clone());
+    // (c^T ⊗ G_n) . that  - A . z = -t_y
+    P.push(row + 1, off_t, PBlock::CotimesG { c: pubin.c.to_vec(), n, base: pp.b1, delta: delta1, neg: false })?;
+    P.push(row + 1, off_z, PBlock::neg_mat(&pp.A))?;
+    match pubin.z_msg {
+        Some(m) => h.extend(m.t_y.iter().map(|t| t.neg(q))),
+        None => h.extend((0..n).map(|_| Poly::zero())),
     }
 
-    // NOTE: we *do not* add the last two Eq.(3) blocks here; the PCS glue will
-    // append them (so it can plug in the Fiat–Shamir vector c and vector a).
-    // Just return what we have; callers will expand P,h further.
-    (P, h)
+    let row_blocks = vec![(0, "v"), (n, "tiers"), (n_outer, "u"), (n + n_outer, "j"),
+                          (row, "c·G·what − a·z"), (row + 1, "c·G·that − A·z")];
+    Ok(R1 { q: *q, P, h, beta_sq: eq14_norm_bound(B), layout, row_blocks })
+}
+
+
+/// Rows spent on the outer commitment: n per node of every tier level, plus n for B.
+pub fn outer_rows(pp: &CommitParams) -> usize {
+    let nodes = pp.level_nodes();
+    pp.n * nodes[1..].iter().sum::<usize>() + pp.n
 }
 
+/// Outer-commitment rows over witness columns [\hat t | tier hats] starting at `off_t`:
+/// for tier j and group g, M_j · group − G_{b,n} · node_g = 0; then B · top = u.
+/// Adds `outer_rows(pp)` rows from `row0` and pushes the matching RHS onto `h`.
+pub fn set_outer_block<'a>(
+    P: &mut SparseP<'a>, h: &mut PolyVec, row0: usize, off_t: usize,
+    pp: &'a CommitParams, u: &PolyVec,
+) -> Result<(), R1Error> {
+    set_outer_block_split(P, h, row0, off_t, off_t + pp.n * pp.delta1 * pp.r, pp, u)
+}
+
+/// As `set_outer_block`, with the tier hats at `off_tiers` instead of right after \hat t.
+pub fn set_outer_block_split<'a>(
+    P: &mut SparseP<'a>, h: &mut PolyVec, row0: usize, off_t: usize, off_tiers: usize,
+    pp: &'a CommitParams, u: &PolyVec,
+) -> Result<(), R1Error> {
+    if u.len() < pp.n { return Err(R1Error::Dimension { what: "u", expected: pp.n, got: u.len() }); }
+    let n = pp.n;
+    let nodes = pp.level_nodes();
+    let mut row = row0;
+    let mut off_in = off_t;
+    let mut off_out = off_tiers;
+    let mut len_in = n * pp.delta1 * pp.r;
+    let mut delta_in = pp.delta1;
+    for (j, t) in pp.tiers.iter().enumerate() {
+        let width = n * delta_in * t.arity;
+        for g in 0..nodes[j + 1] {
+            let cols = width.min(len_in - g * width);
+            P.push(row, off_in + g * width, PBlock::Mat { m: Cow::Borrowed(&t.M), cols, neg: false })?;
+            P.push(row, off_out + g * n * t.delta, PBlock::NegG { n, base: t.b, delta: t.delta })?;
+            h.extend((0..n).map(|_| Poly::zero()));
+            row += n;
+        }
+        off_in = off_out;
+        len_in = n * t.delta * nodes[j + 1];
+        off_out += len_in;
+        delta_in = t.delta;
+    }
+    P.push(row, off_in, PBlock::mat(&pp.B))?;
+    h.extend_from_slice(&u[..n]);
+    Ok(())
+}
 
 impl<'a> ProtoParams<'a> {
-    pub fn ensure_dims(&self) {
+    /// D must be n x δ1·r.
+    pub fn ensure_dims(&self) -> Result<(), R1Error> {
         let pp = self.commit;
-        assert_eq!(self.D.rows, pp.n);
-        assert_eq!(self.D.cols, pp.delta1 * pp.r);
+        for (what, expected, got) in [("D rows", pp.n, self.D.rows), ("D cols", pp.delta1 * pp.r, self.D.cols)] {
+            if got != expected { return Err(R1Error::Dimension { what, expected, got }); }
+        }
+        Ok(())
     }
 }
 
 // w^T = a^T [s1|...|sr]
-pub fn compute_w(a: &PolyVec, s: &[PolyVec], q: &ModQ) -> PolyVec {
+pub fn compute_w(a: &PolyVec, s: &[PolyVec], q: &ModQ) -> Result<PolyVec, R1Error> {
     let r = s.len();
     let mut w = vec![Poly::zero(); r];
     for i in 0..r {
         let mut acc = Poly::zero();
-        assert_eq!(a.len(), s[i].len());
+        if a.len() != s[i].len() { return Err(R1Error::Dimension { what: "s_i", expected: a.len(), got: s[i].len() }); }
         for j in 0..a.len() {
             acc = acc.add(&a[j].mul(&s[i][j], q), q);
         }
         w[i] = acc;
     }
-    w
+    Ok(w)
 }
 
 // z = [s1|...|sr] c
-pub fn compute_z(s: &[PolyVec], c: &[Poly], q: &ModQ) -> PolyVec {
-    let r = s.len(); assert_eq!(r, c.len());
-    let ell = s[0].len(); // δ0 m
+pub fn compute_z(s: &[PolyVec], c: &[Poly], q: &ModQ) -> Result<PolyVec, R1Error> {
+    let r = s.len();
+    if r != c.len() { return Err(R1Error::Dimension { what: "c", expected: r, got: c.len() }); }
+    let ell = s.first().map_or(0, |s0| s0.len()); // δ0 m
+    if let Some(si) = s.iter().find(|si| si.len() != ell) {
+        return Err(R1Error::Dimension { what: "s_i", expected: ell, got: si.len() });
+    }
     let mut z = vec![Poly::zero(); ell];
     for i in 0..r {
         for j in 0..ell {
             z[j] = z[j].add(&s[i][j].mul(&c[i], q), q);
         }
     }
-    z
+    Ok(z)
+}
+
+// ---- Masked amortized opening z = y + S c ----
+//
+// z = [s1|...|sr] c determines S c, which leaks s. With a mask y the prover first
+// sends t_y = A y and w_y = a^T y, draws c from the transcript, and keeps
+// z = y + S c only if rejection sampling makes its distribution independent of
+// S c; otherwise it retries with a fresh y and the next attempt index. The
+// amortization rows then read c^T G what − a^T z = −w_y and
+// (c^T ⊗ G_n) that − A z = −t_y, with z kept in the witness: next to t_y and w_y
+// a z in the clear would give away Σ c_i t_i and c^T w.
+
+/// Distribution of the mask y, and the matching rejection rule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZMask {
+    /// y uniform on [−gamma, gamma]; z kept iff ‖z‖∞ ≤ gamma − T (T bounds ‖S c‖∞).
+    Uniform { gamma: u32 },
+    /// y from the discrete Gaussian D_σ; z kept with probability
+    /// min(1, D_σ(z) / (m · D_{σ,Sc}(z))) and only if ‖z‖∞ ≤ Z_TAIL·σ.
+    Gaussian { sigma: f64, m: f64 },
+}
+
+/// Gaussian tail cut in standard deviations: Pr[|y| > 12σ] < 2^-100 per coefficient.
+pub const Z_TAIL: f64 = 12.0;
+
+/// Attempts before `compute_z_masked` gives up (and the largest index a verifier accepts).
+pub const MAX_Z_ATTEMPTS: u32 = 1 << 10;
+
+/// T = ‖S c‖∞ bound: r columns, ‖c_i‖₁ ≤ τ1 + 2τ2, ‖s_i‖∞ ≤ b0/2 (or β).
+pub fn sc_linf_bound(pp: &CommitParams) -> u64 {
+    let s_inf = match pp.msg_bound { Some(beta) => beta as u64, None => (pp.b0 / 2) as u64 };
+    pp.r as u64 * (TAU1 + 2 * TAU2) as u64 * s_inf
+}
+
+/// ‖S c‖₂ bound: T over each of the δ0·m·d coefficients of z.
+pub fn sc_l2_bound(pp: &CommitParams) -> f64 {
+    sc_linf_bound(pp) as f64 * ((pp.delta0 * pp.m * D) as f64).sqrt()
+}
+
+impl ZMask {
+    /// Uniform mask with about `reps` expected attempts: γ = T·n / ln(reps) for the
+    /// n = δ0·m·d coefficients of z. None unless reps > 1 and the accepted range fits
+    /// below q/2.
+    pub fn uniform_for(pp: &CommitParams, reps: f64) -> Option<ZMask> {
+        if reps.is_nan() || reps <= 1.0 { return None; }
+        let t = sc_linf_bound(pp);
+        let n = (pp.delta0 * pp.m * D) as f64;
+        let gamma = (t as f64 * n / reps.ln()).ceil() as u64 + t;
+        let mask = ZMask::Uniform { gamma: u32::try_from(gamma).ok()? };
+        (mask.z_linf_bound(pp) < (pp.q.q / 2) as u64).then_some(mask)
+    }
+
+    /// Gaussian mask with σ = α·‖S c‖₂ and Lyubashevsky's M = exp(12/α + 1/(2α²)).
+    pub fn gaussian_for(pp: &CommitParams, alpha: f64) -> ZMask {
+        ZMask::Gaussian { sigma: alpha * sc_l2_bound(pp), m: (12.0 / alpha + 1.0 / (2.0 * alpha * alpha)).exp() }
+    }
+
+    /// Expected attempts per accepted z: ((2γ+1)/(2(γ−T)+1))^n for the uniform mask
+    /// (exact, whatever S c is), about M for the Gaussian one.
+    pub fn expected_repetitions(&self, pp: &CommitParams) -> f64 {
+        match *self {
+            ZMask::Uniform { gamma } => {
+                let t = sc_linf_bound(pp);
+                if gamma as u64 <= t { return f64::INFINITY; }
+                let ratio = (2 * gamma as u64 + 1) as f64 / (2 * (gamma as u64 - t) + 1) as f64;
+                ratio.powf((pp.delta0 * pp.m * D) as f64)
+            }
+            ZMask::Gaussian { m, .. } => m,
+        }
+    }
+
+    /// ℓ∞ bound on an accepted z.
+    pub fn z_linf_bound(&self, pp: &CommitParams) -> u64 {
+        match *self {
+            ZMask::Uniform { gamma } => (gamma as u64).saturating_sub(sc_linf_bound(pp)),
+            ZMask::Gaussian { sigma, .. } => (Z_TAIL * sigma).ceil() as u64,
+        }
+    }
+
+    fn sample(&self, q: &ModQ, rng: &mut StdRng) -> u32 {
+        let x = match *self {
+            ZMask::Uniform { gamma } => rng.gen_range(-(gamma as i64)..=gamma as i64),
+            ZMask::Gaussian { sigma, .. } => {
+                let tail = (Z_TAIL * sigma).ceil() as i64;
+                loop {
+                    let x = rng.gen_range(-tail..=tail);
+                    if rng.gen::<f64>() < (-((x * x) as f64) / (2.0 * sigma * sigma)).exp() { break x; }
+                }
+            }
+        };
+        x.rem_euclid(q.q as i64) as u32
+    }
+
+    /// A fresh mask y: δ0·m ring elements with coefficients drawn from the mask.
+    pub fn sample_mask(&self, pp: &CommitParams, rng: &mut StdRng) -> PolyVec {
+        (0..pp.delta0 * pp.m).map(|_| Poly { c: std::array::from_fn(|_| self.sample(&pp.q, rng)) }).collect()
+    }
+
+    // Rejection step for z = y + v, v = S c (centered coefficients).
+    fn accept(&self, z: &[Poly], v: &[Poly], pp: &CommitParams, rng: &mut StdRng) -> bool {
+        let q = &pp.q;
+        let bound = self.z_linf_bound(pp);
+        if z.iter().any(|p| p.linf(q) > bound) { return false; }
+        match *self {
+            ZMask::Uniform { .. } => true,
+            ZMask::Gaussian { sigma, m } => {
+                // D_σ(z) / D_{σ,v}(z) = exp((−2⟨z,v⟩ + ‖v‖²) / 2σ²)
+                let (mut zv, mut vv) = (0f64, 0f64);
+                for (zp, vp) in z.iter().zip(v) {
+                    for t in 0..D {
+                        let (zc, vc) = (q.signed(zp.c[t]) as f64, q.signed(vp.c[t]) as f64);
+                        zv += zc * vc;
+                        vv += vc * vc;
+                    }
+                }
+                let ratio = ((vv - 2.0 * zv) / (2.0 * sigma * sigma)).exp() / m;
+                rng.gen::<f64>() < ratio.min(1.0)
+            }
+        }
+    }
+}
+
+/// The prover's first message for a masked z.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZMaskMsg {
+    pub t_y: PolyVec,   // A y (n)
+    pub w_y: Poly,      // a^T y
+    pub attempt: u32,   // index of the accepted attempt
+}
+
+impl ZMaskMsg {
+    pub fn absorb(&self, fs: &mut Fs) {
+        fs.absorb_u64(self.attempt as u64).absorb_polyvec(&self.t_y).absorb_poly(&self.w_y);
+    }
+}
+
+/// The amortization challenge c for a masked z: `fs` continued with `msg`.
+pub fn masked_challenge(fs: &Fs, pp: &CommitParams, msg: &ZMaskMsg) -> Vec<Poly> {
+    let mut fs = fs.clone();
+    msg.absorb(&mut fs);
+    sample_challenge(&fs, pp)
+}
+
+/// z = y + S c with y drawn from `mask` and c = `masked_challenge(fs, ..)`, retried
+/// with a fresh y until rejection sampling accepts. Returns z, the first message
+/// and c.
+pub fn compute_z_masked(
+    pp: &CommitParams,
+    mask: &ZMask,
+    a: &[Poly],
+    s: &[PolyVec],
+    fs: &Fs,
+    rng: &mut StdRng,
+) -> Result<(PolyVec, ZMaskMsg, Vec<Poly>), R1Error> {
+    let q = &pp.q;
+    let ell = pp.delta0 * pp.m;
+    if a.len() != ell { return Err(R1Error::Dimension { what: "a", expected: ell, got: a.len() }); }
+    for attempt in 0..MAX_Z_ATTEMPTS {
+        // y, S c and a rejected z are all wiped when dropped
+        let y = SecretPolyVec::new(mask.sample_mask(pp, rng));
+        let t_y = pp.A.mul_vec(&y, q);
+        let w_y = a.iter().zip(y.iter()).fold(Poly::zero(), |acc, (ai, yi)| acc.add(&ai.mul(yi, q), q));
+        let msg = ZMaskMsg { t_y, w_y, attempt };
+        let c = masked_challenge(fs, pp, &msg);
+        let v = SecretPolyVec::new(compute_z(s, &c, q)?);
+        let z = SecretPolyVec::new(y.iter().zip(v.iter()).map(|(yi, vi)| yi.add(vi, q)).collect());
+        if mask.accept(&z, &v, pp, rng) { return Ok((z.to_vec(), msg, c)); }
+    }
+    Err(R1Error::Rejected { attempts: MAX_Z_ATTEMPTS })
+}
+
+/// Message row a^T = a0^T G_{b0,m} (length δ0 m) for the "c^T G \hat w = a^T z" row.
+/// In short-message mode δ0 = 1 and G_{b0,m} is the identity, so a = a0 and the
+/// z block of the relation shrinks from δ0 m to m columns.
+pub fn message_row(pp: &CommitParams, a0: &PolyVec, q: &ModQ) -> PolyVec {
+    assert_eq!(a0.len(), pp.m);
+    if pp.msg_bound.is_some() { return a0.clone(); }
+    row_vec_times_G(a0, pp.b0, pp.delta0, q)
 }
 
 // row for b^T G_{b1,r}
@@ -188,63 +986,139 @@ pub fn cotimes_G_block(c: &[Poly], n: usize, base: u32, delta: usize, q: &ModQ)
     M
 }
 
-// Build P,h as Eq. (3)
-pub fn build_linear_system(
-    params: &ProtoParams,
+// Build P,h as Eq. (3); Z = [what | that | tiers | z]
+pub fn build_linear_system<'a>(
+    params: &ProtoParams<'a>,
     a: &PolyVec, b: &PolyVec, u: &PolyVec, v: &PolyVec, y_rhs: &Poly, c: &[Poly],
-) -> (MatrixRq, PolyVec) {
-    params.ensure_dims();
+) -> Result<R1<'a>, R1Error> {
+    params.ensure_dims()?;
     let pp = params.commit; let q = &pp.q;
+    for (what, expected, got) in [("v", pp.n, v.len()), ("u", pp.n, u.len()), ("b", pp.r, b.len()),
+                                  ("c", pp.r, c.len()), ("a", pp.delta0 * pp.m, a.len())] {
+        if got != expected { return Err(R1Error::Dimension { what, expected, got }); }
+    }
 
-    let rows = 3*pp.n + 2;
-    let cols = pp.delta1*pp.r + (pp.n*pp.delta1*pp.r) + (pp.delta0*pp.m);
-    let mut P = MatrixRq::zeros(rows, cols);
+    let layout = Layout::new()
+        .with(Block::What, pp.delta1 * pp.r)?
+        .with(Block::That, pp.n * pp.delta1 * pp.r)?
+        .with(Block::Tiers, pp.tier_witness_len())?
+        .with(Block::Z, pp.delta0 * pp.m)?;
+    let n_outer = outer_rows(pp);
+    let rows = 2*pp.n + 2 + n_outer;
+    let mut P = SparseP::new(rows, layout.cols());
+    let mut h = Vec::<Poly>::with_capacity(rows);
 
-    let off_w = 0usize;
-    let off_t = off_w + pp.delta1 * pp.r;
-    let off_z = off_t + (pp.n * pp.delta1 * pp.r);
+    let off_w = layout.offset(Block::What);
+    let off_t = layout.offset(Block::That);
+    let off_z = layout.offset(Block::Z);
 
     // Block 1: D
-    for rrow in 0..pp.n {
-        for j in 0..(pp.delta1*pp.r) {
-            P.set(rrow, off_w + j, params.D.at(rrow, j).clone());
-        }
-    }
-    // Block 2: B
-    for rrow in 0..pp.n {
-        let dst = pp.n + rrow;
-        for j in 0..(pp.n*pp.delta1*pp.r) {
-            P.set(dst, off_t + j, pp.B.at(rrow, j).clone());
-        }
-    }
+    P.push(0, off_w, PBlock::mat(params.D))?;
+    h.extend_from_slice(&v[..pp.n]);
+    // Block 2: tiers (if any), then B
+    set_outer_block(&mut P, &mut h, pp.n, off_t, pp, u)?;
     // Row 3: b^T G . w^
-    let row3 = 2*pp.n;
-    let row_bG = row_vec_times_G(b, pp.b1, pp.delta1, q);
-    for j in 0..row_bG.len() { P.set(row3, off_w + j, row_bG[j].clone()); }
+    let row3 = pp.n + n_outer;
+    P.push(row3, off_w, PBlock::GadgetRow { v: b.clone(), base: pp.b1, delta: pp.delta1 })?;
 
     // Row 4: c^T G . w^   and   -a^ on z
-    let row4 = 2*pp.n + 1;
-    let row_cG = row_vec_times_G(&c.to_vec(), pp.b1, pp.delta1, q);
-    for j in 0..row_cG.len() { P.set(row4, off_w + j, row_cG[j].clone()); }
-    for j in 0..a.len() { P.set(row4, off_z + j, a[j].neg(q)); }
+    let row4 = row3 + 1;
+    P.push(row4, off_w, PBlock::GadgetRow { v: c.to_vec(), base: pp.b1, delta: pp.delta1 })?;
+    P.push(row4, off_z, PBlock::Row(a.iter().map(|p| p.neg(q)).collect()))?;
 
     // Block 5: n rows — (c^T ⊗ G).t^  and  -A.z
-    let block = cotimes_G_block(c, pp.n, pp.b1, pp.delta1, q);
-    for rrow in 0..pp.n {
-        let dst = 2*pp.n + 2 + rrow;
-        for j in 0..(pp.n*pp.delta1*pp.r) { P.set(dst, off_t + j, block.at(rrow, j).clone()); }
-        for j in 0..(pp.delta0*pp.m)       { P.set(dst, off_z + j, pp.A.at(rrow, j).neg(q)); }
-    }
+    P.push(row4 + 1, off_t, PBlock::CotimesG { c: c.to_vec(), n: pp.n, base: pp.b1, delta: pp.delta1, neg: false })?;
+    P.push(row4 + 1, off_z, PBlock::neg_mat(&pp.A))?;
 
-    // h = [v ; u ; y ; 0 ; 0_n]
-    let mut h = Vec::<Poly>::with_capacity(rows);
-    for i in 0..pp.n { h.push(v[i].clone()); }
-    for i in 0..pp.n { h.push(u[i].clone()); }
+    // h = [v ; 0_tiers ; u ; y ; 0 ; 0_n]
     h.push(y_rhs.clone());
     h.push(Poly::zero());
     for _ in 0..pp.n { h.push(Poly::zero()); }
 
-    (P, h)
+    let row_blocks = vec![(0, "v"), (pp.n, "tiers"), (row3 - pp.n, "u"), (row3, "y"),
+                          (row4, "c·G·what − a·z"), (row4 + 1, "c·G·that − A·z")];
+    Ok(R1 { q: *q, P, h, beta_sq: eq3_norm_bound(pp), layout, row_blocks })
+}
+
+/// One point group of a batch opening after folding its polynomials with ring
+/// challenges e_ℓ: the a row (with G_{b0}), the b row, the folded outer commitment
+/// U = Σ e_ℓ u_ℓ, the r amortization challenges c, and `fold` = Σ ‖e_ℓ‖₁ (1 for a
+/// single polynomial), which scales the bounds on \hat t, tiers and z.
+pub struct BatchGroup<'g> {
+    pub a: &'g PolyVec,
+    pub b: &'g PolyVec,
+    pub u: &'g PolyVec,
+    pub c: &'g [Poly],
+    pub fold: u32,
+}
+
+/// Batched Eq. (3) over point groups g, Z = [\hat w_g.. | \hat t_g.. | tiers_g.. | z_g..]:
+///   Σ_g D_g \hat w_g = v,  outer rows per g against U_g,
+///   Σ_g ρ_g b_g^T G \hat w_g = y,
+///   per group: c^T G \hat w_g − a_g^T z_g = 0 and (c^T ⊗ G_n) \hat t_g − A z_g = 0.
+/// The witness width depends on the number of groups, not on the number of polynomials.
+pub fn build_batch_system<'a>(
+    pp: &'a CommitParams, Ds: &'a [MatrixRq], rho: &[u32],
+    groups: &[BatchGroup], v: &PolyVec, y_rhs: &Poly,
+) -> Result<R1<'a>, R1Error> {
+    let q = &pp.q;
+    let k = groups.len();
+    let check = |what, expected, got| if expected == got { Ok(()) } else { Err(R1Error::Dimension { what, expected, got }) };
+    check("D", k, Ds.len())?;
+    check("rho", k, rho.len())?;
+    check("v", pp.n, v.len())?;
+    for g in groups {
+        check("c", pp.r, g.c.len())?;
+        check("u", pp.n, g.u.len())?;
+    }
+    let (len_w, len_t, len_tiers) = (pp.delta1 * pp.r, pp.n * pp.delta1 * pp.r, pp.tier_witness_len());
+    let len_z = pp.delta0 * pp.m;
+    let layout = Layout::new()
+        .with(Block::What, k * len_w)?
+        .with(Block::That, k * len_t)?
+        .with(Block::Tiers, k * len_tiers)?
+        .with(Block::Z, k * len_z)?;
+    let n_outer = outer_rows(pp);
+    let rows = pp.n + k * n_outer + 1 + k * (1 + pp.n);
+    let mut P = SparseP::new(rows, layout.cols());
+    let mut h = Vec::<Poly>::with_capacity(rows);
+    let (off_w, off_t) = (layout.offset(Block::What), layout.offset(Block::That));
+    let (off_tiers, off_z) = (layout.offset(Block::Tiers), layout.offset(Block::Z));
+
+    // Σ_g D_g \hat w_g = v
+    for (g, Dg) in Ds.iter().enumerate() { P.push(0, off_w + g * len_w, PBlock::mat(Dg))?; }
+    h.extend_from_slice(v);
+    // folded outer commitments
+    for (gi, g) in groups.iter().enumerate() {
+        set_outer_block_split(&mut P, &mut h, pp.n + gi * n_outer, off_t + gi * len_t, off_tiers + gi * len_tiers, pp, g.u)?;
+    }
+    // Σ_g ρ_g b_g^T G \hat w_g = y
+    let mut row = pp.n + k * n_outer;
+    for (gi, g) in groups.iter().enumerate() {
+        let v = g.b.iter().map(|p| scale(p, rho[gi], q)).collect();
+        P.push(row, off_w + gi * len_w, PBlock::GadgetRow { v, base: pp.b1, delta: pp.delta1 })?;
+    }
+    h.push(y_rhs.clone());
+    row += 1;
+    // amortization per group
+    for (gi, g) in groups.iter().enumerate() {
+        P.push(row, off_w + gi * len_w, PBlock::GadgetRow { v: g.c.to_vec(), base: pp.b1, delta: pp.delta1 })?;
+        P.push(row + 1, off_t + gi * len_t, PBlock::CotimesG { c: g.c.to_vec(), n: pp.n, base: pp.b1, delta: pp.delta1, neg: false })?;
+        P.push(row, off_z + gi * len_z, PBlock::Row(g.a.iter().map(|p| p.neg(q)).collect()))?;
+        P.push(row + 1, off_z + gi * len_z, PBlock::neg_mat(&pp.A))?;
+        h.extend((0..1 + pp.n).map(|_| Poly::zero()));
+        row += 1 + pp.n;
+    }
+
+    let folds: Vec<u32> = groups.iter().map(|g| g.fold).collect();
+    let mut row_blocks = vec![(0, "v")];
+    for g in 0..k { row_blocks.push((pp.n + g * n_outer, "outer commitment")); }
+    row_blocks.push((pp.n + k * n_outer, "y"));
+    for gi in 0..k {
+        let row = pp.n + k * n_outer + 1 + gi * (1 + pp.n);
+        row_blocks.extend([(row, "c·G·what − a·z"), (row + 1, "c·G·that − A·z")]);
+    }
+    Ok(R1 { q: *q, P, h, beta_sq: batch_norm_bound(pp, &folds), layout, row_blocks })
 }
 
 // \hat w and v = D \hat w
@@ -260,15 +1134,708 @@ pub fn derive_w_hat_and_v(
 
 
 // Paper’s τ1=32, τ2=8 for d=64
+pub const TAU1: usize = 32;
+pub const TAU2: usize = 8;
+
 pub fn sample_challenge(fs: &Fs, pp: &CommitParams) -> Vec<Poly> {
-    fs.challenge_vec(pp.r, &pp.q, 32, 8)
+    fs.challenge_vec(pp.r, &pp.q, TAU1, TAU2)
+}
+
+// ===================== JL approximate range proof =====================
+//
+// The verifier's ternary Π (rows x 64·len, from the transcript) projects coeffs(Z)
+// to p = Π·coeffs(Z), which is sent in the clear; ‖p‖² ≈ (rows/2)‖Z‖². Consistency
+// with Z is reduced to ring rows ⟨φ''_k, Z⟩ = b''_k whose constant terms are the
+// random combinations Σ_j ω_kj p_j; the caller proves those rows with its own
+// linear argument. A short p then bounds ‖Z‖ up to the JL slack.
+
+/// Projection dimension. Fixed: the soundness bound in `JlParams::proven_bound`
+/// (LaBRADOR, Lemma 2.2) is stated for exactly 256 rows.
+pub const JL_ROWS: usize = 256;
+
+/// JL parameters: the slack on the accepted ‖p‖.
+#[derive(Clone, Debug)]
+pub struct JlParams {
+    pub slack: u32,       // accept ‖p‖² ≤ slack·(JL_ROWS/2)·β²; larger means fewer retries
+}
+
+impl Default for JlParams {
+    fn default() -> Self { Self { slack: 1 } }
+}
+
+impl JlParams {
+    /// Largest ‖p‖² the verifier accepts for a witness bound β².
+    pub fn p_bound(&self, beta_sq: u128) -> u128 {
+        (self.slack as u128 * JL_ROWS as u128 / 2).saturating_mul(beta_sq)
+    }
+
+    /// Bound on ‖Z‖² implied by an accepted p: Pr[‖Πw‖² < 30‖w‖²] ≈ 2^-128 for
+    /// the 256 rows of Π (LaBRADOR, Lemma 2.2).
+    pub fn proven_bound(&self, beta_sq: u128) -> u128 {
+        self.p_bound(beta_sq) / 30 + 1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JlProof {
+    pub nonce: u64,        // retry counter
+    pub p: Vec<i64>,       // Π·coeffs(Z)
+    pub b_lift: PolyVec,   // ⟨φ''_k, Z⟩, with ct(b''_k) = Σ_j ω_kj p_j
+}
+
+// Lifted JL checks: ⌈128 / log q⌉ so that a bad p survives with prob. ≈ 2^-128.
+fn jl_lifts(q: &ModQ) -> usize {
+    let bits = (32 - q.q.leading_zeros()) as usize - 1;
+    128usize.div_ceil(bits.max(1))
+}
+
+fn jl_matrix(fs: &Fs, nonce: u64, rows: usize, len: usize) -> Vec<i8> {
+    let mut fs = fs.clone();
+    fs.absorb_bytes(b"jl").absorb_u64(nonce);
+    fs.ternary(rows * len * D)
+}
+
+fn jl_project(pi: &[i8], rows: usize, Z: &PolyVec, q: &ModQ) -> Vec<i64> {
+    let coeffs: Vec<i64> = Z.iter().flat_map(|p| p.c.iter().map(|&x| q.signed(x))).collect();
+    (0..rows)
+        .map(|j| pi[j * coeffs.len()..(j + 1) * coeffs.len()].iter().zip(&coeffs).map(|(&a, &x)| a as i64 * x).sum())
+        .collect()
+}
+
+// φ''_k = σ^{-1}(Σ_j ω_kj π_j), so ct(⟨φ''_k, Z⟩) = Σ_j ω_kj ⟨π_j, coeffs(Z)⟩.
+fn jl_lift_rows(pi: &[i8], rows: usize, len: usize, omega: &[u32], q: &ModQ) -> Vec<PolyVec> {
+    let width = len * D;
+    omega.chunks(rows).map(|om| {
+        let mut acc = vec![0u32; width];
+        for (j, &w) in om.iter().enumerate() {
+            for (t, &a) in pi[j * width..(j + 1) * width].iter().enumerate() {
+                if a == 1 { acc[t] = q.add(acc[t], w); } else if a == -1 { acc[t] = q.sub(acc[t], w); }
+            }
+        }
+        acc.chunks(D).map(|c| {
+            let mut p = Poly::zero();
+            p.c.copy_from_slice(c);
+            p.sigma_inv(q)
+        }).collect()
+    }).collect()
+}
+
+fn absorb_p(fs: &mut Fs, nonce: u64, p: &[i64]) {
+    fs.absorb_u64(nonce);
+    for &pj in p { fs.absorb_u64(pj as u64); }
+}
+
+// ‖p‖², or None if it does not fit in a u128 (p comes from the prover).
+fn norm_sq_i64(p: &[i64]) -> Option<u128> {
+    p.iter().try_fold(0u128, |acc, &x| acc.checked_add((x.unsigned_abs() as u128).pow(2)))
+}
+
+/// Project Z (retrying with a fresh Π until ‖p‖ fits) and lift the projection.
+/// Returns the proof and the rows φ''_k; ⟨φ''_k, Z⟩ = b''_k is left to the caller.
+pub fn jl_prove(jp: &JlParams, fs: &mut Fs, Z: &PolyVec, beta_sq: u128, q: &ModQ) -> (JlProof, Vec<PolyVec>) {
+    let bound = jp.p_bound(beta_sq);
+    let mut nonce = 0u64;
+    let (pi, p) = loop {
+        let pi = jl_matrix(fs, nonce, JL_ROWS, Z.len());
+        let p = jl_project(&pi, JL_ROWS, Z, q);
+        if norm_sq_i64(&p).is_some_and(|n| n <= bound) { break (pi, p); }
+        nonce += 1;
+    };
+    absorb_p(fs, nonce, &p);
+    let omega = fs.alphas(jl_lifts(q) * JL_ROWS, q);
+    let lift_rows = jl_lift_rows(&pi, JL_ROWS, Z.len(), &omega, q);
+    let b_lift: PolyVec = lift_rows.iter().map(|row| inner(row, Z, q)).collect();
+    fs.absorb_polyvec(&b_lift);
+    (JlProof { nonce, p, b_lift }, lift_rows)
+}
+
+/// Check ‖p‖ and the constant terms of b''; on success return the rows φ''_k the
+/// caller must still tie to Z.
+pub fn jl_verify(jp: &JlParams, fs: &mut Fs, len: usize, beta_sq: u128, proof: &JlProof, q: &ModQ) -> Result<Vec<PolyVec>, ProofError> {
+    let lifts = jl_lifts(q);
+    if proof.p.len() != JL_ROWS { return Err(ProofError::Dimension { what: "p", expected: JL_ROWS, got: proof.p.len() }); }
+    if proof.b_lift.len() != lifts { return Err(ProofError::Dimension { what: "b''", expected: lifts, got: proof.b_lift.len() }); }
+    let bound = jp.p_bound(beta_sq);
+    match norm_sq_i64(&proof.p) {
+        Some(norm_sq) if norm_sq <= bound => {}
+        norm_sq => return Err(ProofError::JlNorm { norm_sq: norm_sq.unwrap_or(u128::MAX), bound }),
+    }
+    let pi = jl_matrix(fs, proof.nonce, JL_ROWS, len);
+    absorb_p(fs, proof.nonce, &proof.p);
+    let omega = fs.alphas(lifts * JL_ROWS, q);
+    for (l, om) in omega.chunks(JL_ROWS).enumerate() {
+        let mut acc = 0u32;
+        for (j, &w) in om.iter().enumerate() {
+            let pj = proof.p[j].rem_euclid(q.q as i64) as u32;
+            acc = q.add(acc, q.mul(w, pj));
+        }
+        if proof.b_lift[l].ct() != acc { return Err(ProofError::JlLift { lift: l }); }
+    }
+    let lift_rows = jl_lift_rows(&pi, JL_ROWS, len, &omega, q);
+    fs.absorb_polyvec(&proof.b_lift);
+    Ok(lift_rows)
+}
+
+/// Why a JL, LaBRADOR or norm proof was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// The proof has `got` LaBRADOR rounds where the schedule has `expected`.
+    Rounds { expected: usize, got: usize },
+    /// Wrong length of proof part `what` ("p", "b''", "u1", "t", "z", ...).
+    Dimension { what: &'static str, expected: usize, got: usize },
+    /// ‖p‖² of the JL projection is above its bound (u128::MAX: it overflows).
+    JlNorm { norm_sq: u128, bound: u128 },
+    /// ct(b''_l) ≠ Σ_j ω_lj p_j.
+    JlLift { lift: usize },
+    /// The LaBRADOR base witness does not satisfy the final relation.
+    Base(R1Error),
+    /// The next round's relation could not be built.
+    Relation(R1Error),
+    /// A norm-proof check failed; names the equation.
+    Norm(&'static str),
+    /// `err` happened in LaBRADOR round `round`.
+    Round { round: usize, err: Box<ProofError> },
+}
+
+impl std::fmt::Display for ProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProofError::Rounds { expected, got } => write!(f, "expected {} rounds, got {}", expected, got),
+            ProofError::Dimension { what, expected, got } =>
+                write!(f, "dimension mismatch in {}: expected {}, got {}", what, expected, got),
+            ProofError::JlNorm { norm_sq, bound } => write!(f, "JL projection ‖p‖² = {} exceeds bound {}", norm_sq, bound),
+            ProofError::JlLift { lift } => write!(f, "ct(b''_{}) mismatch", lift),
+            ProofError::Base(e) => write!(f, "base witness: {}", e),
+            ProofError::Relation(e) => write!(f, "next relation: {}", e),
+            ProofError::Norm(what) => write!(f, "norm proof: check {} failed", what),
+            ProofError::Round { round, err } => write!(f, "round {}: {}", round, err),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+// ===================== LaBRADOR for R1: P·Z = h, ‖Z‖₂ ≤ β =====================
+//
+// One round (linear constraints only): split Z into r chunks s_i, commit
+// t_i = A s_i and u1 = B \hat t; JL-project Z and lift the projection checks to
+// ring constraints; fold every row into one ⟨φ, Z⟩ = b; commit the garbage
+// h_ij = ⟨φ_i, s_j⟩ + ⟨φ_j, s_i⟩ as u2 = C \hat h; answer challenges c with
+// z = Σ c_i s_i. The verifier's checks on (z, \hat t, \hat h) are again linear, so
+// the next round proves a new R1 instance on Z' = [z | \hat t | \hat h].
+
+/// LaBRADOR parameters (toy ranks; see the LaBRADOR paper, Sec. 5, for real ones).
+#[derive(Clone, Debug)]
+pub struct LabradorParams {
+    pub kappa: usize,     // inner rank: A is κ x (chunk length)
+    pub kappa1: usize,    // outer ranks: B, C
+    pub b: u32,           // decomposition base for \hat t and \hat h
+    pub jl: JlParams,     // JL projection of each round's witness
+    pub base_len: usize,  // send Z in the clear once |Z| ≤ base_len
+    pub max_rounds: usize,
+    pub seed: u64,        // A, B, C of round k are expanded from (seed, k)
+}
+
+impl Default for LabradorParams {
+    fn default() -> Self {
+        Self { kappa: 2, kappa1: 2, b: 7, jl: JlParams::default(), base_len: 64, max_rounds: 8, seed: 0x1AB }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabradorRound {
+    pub u1: PolyVec,       // B \hat t
+    pub jl: JlProof,
+    pub u2: PolyVec,       // C \hat h
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabradorProof {
+    pub rounds: Vec<LabradorRound>,
+    pub base: PolyVec,     // last-round witness in the clear
+}
+
+impl LabradorParams {
+    pub fn write(&self, w: &mut Writer) {
+        w.put_len(self.kappa).put_len(self.kappa1).put_u32(self.b)
+            .put_len(JL_ROWS).put_u32(self.jl.slack)
+            .put_len(self.base_len).put_len(self.max_rounds).put_u64(self.seed);
+    }
+
+    pub fn read(rd: &mut Reader) -> Result<Self, DecodeError> {
+        let (kappa, kappa1, b) = (rd.get_u32()? as usize, rd.get_u32()? as usize, rd.get_u32()?);
+        let jl_rows = rd.get_u32()? as usize;
+        let jl = JlParams { slack: rd.get_u32()? };
+        let (base_len, max_rounds, seed) = (rd.get_u32()? as usize, rd.get_u32()? as usize, rd.get_u64()?);
+        if b < 2 { return Err(DecodeError::Invalid("LaBRADOR base below 2")); }
+        if jl_rows != JL_ROWS { return Err(DecodeError::Invalid("JL rows other than 256")); }
+        Ok(Self { kappa, kappa1, b, jl, base_len, max_rounds, seed })
+    }
+}
+
+impl LabradorProof {
+    /// rounds (u1, nonce, p, b_lift, u2 each), then the base witness.
+    pub fn write(&self, w: &mut Writer) {
+        w.put_len(self.rounds.len());
+        for rd in &self.rounds {
+            w.put_polys(&rd.u1).put_u64(rd.jl.nonce).put_len(rd.jl.p.len());
+            for &x in &rd.jl.p { w.put_u64(x as u64); }
+            w.put_polys(&rd.jl.b_lift).put_polys(&rd.u2);
+        }
+        w.put_polys(&self.base);
+    }
+
+    /// Reads a proof and checks its per-round shapes against `lp` over `q`.
+    pub fn read(rd: &mut Reader, lp: &LabradorParams, q: &ModQ) -> Result<Self, DecodeError> {
+        let n = rd.get_len(1)?;
+        if n > lp.max_rounds { return Err(DecodeError::Dimension { what: "LaBRADOR rounds", expected: lp.max_rounds, got: n }); }
+        let mut rounds = Vec::with_capacity(n);
+        for _ in 0..n {
+            let u1 = rd.get_polys_exact("u1", lp.kappa1)?;
+            let nonce = rd.get_u64()?;
+            let len = rd.get_len(8)?;
+            if len != JL_ROWS { return Err(DecodeError::Dimension { what: "JL projection", expected: JL_ROWS, got: len }); }
+            let p = (0..len).map(|_| rd.get_u64().map(|x| x as i64)).collect::<Result<_, _>>()?;
+            let b_lift = rd.get_polys_exact("JL lifts", jl_lifts(q))?;
+            let u2 = rd.get_polys_exact("u2", lp.kappa1)?;
+            rounds.push(LabradorRound { u1, jl: JlProof { nonce, p, b_lift }, u2 });
+        }
+        Ok(Self { rounds, base: rd.get_polys()? })
+    }
+}
+
+/// Number of chunks r and chunk length for a round on a witness of `len` ring
+/// elements, chosen to minimise the next witness; None when no split shrinks it.
+fn labrador_split(lp: &LabradorParams, len: usize, q: &ModQ) -> Option<(usize, usize)> {
+    let delta = digits_for(q, lp.b);
+    let next = |r: usize| {
+        let chunk = len.div_ceil(r);
+        (chunk + r * lp.kappa * delta + r * (r + 1) / 2 * delta, chunk)
+    };
+    let (best, r) = (1..=len).map(|r| (next(r).0, r)).min()?;
+    (best < len).then(|| (r, next(r).1))
+}
+
+/// Round schedule shared by prover and verifier: (r, chunk) per round.
+pub fn labrador_schedule(lp: &LabradorParams, len: usize, q: &ModQ) -> Vec<(usize, usize)> {
+    let delta = digits_for(q, lp.b);
+    let mut out = Vec::new();
+    let mut len = len;
+    while out.len() < lp.max_rounds && len > lp.base_len {
+        let Some((r, chunk)) = labrador_split(lp, len, q) else { break };
+        out.push((r, chunk));
+        len = chunk + r * lp.kappa * delta + r * (r + 1) / 2 * delta;
+    }
+    out
+}
+
+struct RoundMats { A: MatrixRq, B: MatrixRq, C: MatrixRq }
+
+fn round_mats(lp: &LabradorParams, q: &ModQ, k: usize, r: usize, chunk: usize) -> RoundMats {
+    let delta = digits_for(q, lp.b);
+    RoundMats {
+        A: expand_block(q, lp.kappa, chunk, lp.seed, 3 * k),
+        B: expand_block(q, lp.kappa1, r * lp.kappa * delta, lp.seed, 3 * k + 1),
+        C: expand_block(q, lp.kappa1, r * (r + 1) / 2 * delta, lp.seed, 3 * k + 2),
+    }
+}
+
+// Fold all rows of P·Z = h and the lifted JL rows into one ⟨φ, Z⟩ = b.
+fn fold_rows(rel: &R1, lifts: &[PolyVec], b_lift: &PolyVec, alpha: &[u32], q: &ModQ) -> (PolyVec, Poly) {
+    // φ = P^T α (α as constant polys), b = ⟨α, h⟩
+    let a: PolyVec = alpha[..rel.P.rows].iter().map(|&x| Poly::monomial(0, x, q)).collect();
+    let mut phi = rel.P.mul_transpose(&a, q);
+    let mut b = Poly::zero();
+    for (hi, &ai) in rel.h.iter().zip(&alpha[..rel.P.rows]) { b = b.add(&scale(hi, ai, q), q); }
+    for (l, row) in lifts.iter().enumerate() {
+        let a = alpha[rel.P.rows + l];
+        for k in 0..rel.P.cols { phi[k] = phi[k].add(&scale(&row[k], a, q), q); }
+        b = b.add(&scale(&b_lift[l], a, q), q);
+    }
+    (phi, b)
+}
+
+fn chunks_of(v: &PolyVec, r: usize, chunk: usize) -> Vec<PolyVec> {
+    (0..r).map(|i| {
+        (i * chunk..(i + 1) * chunk).map(|k| v.get(k).cloned().unwrap_or_else(Poly::zero)).collect()
+    }).collect()
+}
+
+fn inner(a: &PolyVec, b: &PolyVec, q: &ModQ) -> Poly {
+    a.iter().zip(b).fold(Poly::zero(), |acc, (x, y)| acc.add(&x.mul(y, q), q))
+}
+
+/// What one round leaves for the next relation: the folded row ⟨φ, Z⟩ = b,
+/// the amortization challenges c and both commitments u1, u2.
+struct RoundOut<'a> {
+    phi: &'a PolyVec,
+    b: &'a Poly,
+    c: &'a [Poly],
+    u1: &'a PolyVec,
+    u2: &'a PolyVec,
+}
+
+/// Next-round relation on Z' = [z | \hat t | \hat h]:
+///   A z − (c^T ⊗ G_κ) \hat t = 0,  B \hat t = u1,  C \hat h = u2,
+///   ⟨Σ c_i φ_i, z⟩ − Σ_{i≤j} c_i c_j G \hat h_ij = 0,  Σ_i G \hat h_ii = b.
+fn next_relation(
+    lp: &LabradorParams, q: &ModQ, mats: RoundMats, r: usize, chunk: usize, out: RoundOut, beta_sq: u128,
+) -> Result<R1<'static>, R1Error> {
+    let RoundOut { phi, b, c, u1, u2 } = out;
+    let delta = digits_for(q, lp.b);
+    let (kappa, kappa1) = (lp.kappa, lp.kappa1);
+    let len_t = r * kappa * delta;
+    let pairs = r * (r + 1) / 2;
+    let (off_t, off_h) = (chunk, chunk + len_t);
+    let rows = kappa + 2 * kappa1 + 2;
+    let mut P = SparseP::new(rows, off_h + pairs * delta);
+    let mut h = Vec::with_capacity(rows);
+    let owned = |m: MatrixRq| { let cols = m.cols; PBlock::Mat { m: Cow::Owned(m), cols, neg: false } };
+
+    P.push(0, 0, owned(mats.A))?;
+    P.push(0, off_t, PBlock::CotimesG { c: c.to_vec(), n: kappa, base: lp.b, delta, neg: true })?;
+    h.extend((0..kappa).map(|_| Poly::zero()));
+    P.push(kappa, off_t, owned(mats.B))?;
+    h.extend_from_slice(&u1[..kappa1]);
+    P.push(kappa + kappa1, off_h, owned(mats.C))?;
+    h.extend_from_slice(&u2[..kappa1]);
+
+    let row = kappa + 2 * kappa1;
+    let phis = chunks_of(phi, r, chunk);
+    let coef = (0..chunk).map(|k| (0..r).fold(Poly::zero(), |acc, i| acc.add(&c[i].mul(&phis[i][k], q), q))).collect();
+    P.push(row, 0, PBlock::Row(coef))?;
+    let mut cc = Vec::with_capacity(pairs);
+    let mut diag = Vec::with_capacity(pairs);
+    for i in 0..r {
+        for j in i..r {
+            cc.push(c[i].mul(&c[j], q).neg(q));
+            diag.push(if i == j { Poly::monomial(0, 1, q) } else { Poly::zero() });
+        }
+    }
+    P.push(row, off_h, PBlock::GadgetRow { v: cc, base: lp.b, delta })?;
+    h.push(Poly::zero());
+    P.push(row + 1, off_h, PBlock::GadgetRow { v: diag, base: lp.b, delta })?;
+    h.push(b.clone());
+
+    // ‖c_i s_i‖ ≤ ‖c_i‖₁‖s_i‖, so ‖z‖² ≤ (τ1 + 2τ2)² r β²; digits are at most ⌊b/2⌋.
+    let c_l1 = (TAU1 + 2 * TAU2) as u128;
+    let half = (lp.b / 2) as u128;
+    let digits = ((len_t + pairs * delta) * D) as u128;
+    let beta_sq = (c_l1 * c_l1 * r as u128).saturating_mul(beta_sq).saturating_add(digits * half * half);
+    let layout = Layout::new().with(Block::Z, chunk)?.with(Block::That, len_t)?.with(Block::Garbage, pairs * delta)?;
+    let row_blocks = vec![(0, "A z − c·G·t"), (kappa, "u1"), (kappa + kappa1, "u2"), (row, "⟨φ, z⟩ − c·c·G·h"), (row + 1, "b")];
+    Ok(R1 { q: *q, P, h, beta_sq, layout, row_blocks })
+}
+
+fn labrador_fs(rel: &R1) -> Fs {
+    let mut fs = Fs::new(b"greyhound/labrador");
+    rel.P.absorb(&mut fs);
+    fs.absorb_polyvec(&rel.h).absorb_bytes(&rel.beta_sq.to_le_bytes());
+    fs
+}
+
+/// Prove P·Z = h, ‖Z‖₂ ≤ β by recursive LaBRADOR rounds down to `lp.base_len`.
+pub fn labrador_prove(lp: &LabradorParams, rel: &R1, Z: PolyVec) -> Result<LabradorProof, ProofError> {
+    let q = &rel.q;
+    if Z.len() != rel.P.cols { return Err(ProofError::Dimension { what: "Z", expected: rel.P.cols, got: Z.len() }); }
+    let mut fs = labrador_fs(rel);
+    let delta = digits_for(q, lp.b);
+    let lifts = jl_lifts(q);
+    let mut rel = rel.clone();
+    let mut Z = Z;
+    let mut rounds = Vec::new();
+
+    for (k, (r, chunk)) in labrador_schedule(lp, Z.len(), q).into_iter().enumerate() {
+        let mats = round_mats(lp, q, k, r, chunk);
+        let s = chunks_of(&Z, r, chunk);
+
+        // inner/outer commitments
+        let mut that = Vec::with_capacity(r * lp.kappa * delta);
+        for si in &s { that.extend(g_inv_vec(&mats.A.mul_vec(si, q), lp.b, q)); }
+        let u1 = mats.B.mul_vec(&that, q);
+        fs.absorb_polyvec(&u1);
+
+        // JL projection of Z; its lifted rows are folded in with P
+        let (jl, lift_rows) = jl_prove(&lp.jl, &mut fs, &Z, rel.beta_sq, q);
+
+        // fold into ⟨φ, Z⟩ = b, then commit the garbage
+        let alpha = fs.alphas(rel.P.rows + lifts, q);
+        let (phi, b) = fold_rows(&rel, &lift_rows, &jl.b_lift, &alpha, q);
+        let phis = chunks_of(&phi, r, chunk);
+        let mut garbage = Vec::with_capacity(r * (r + 1) / 2);
+        for i in 0..r {
+            for j in i..r {
+                let g = inner(&phis[i], &s[j], q);
+                garbage.push(if i == j { g } else { g.add(&inner(&phis[j], &s[i], q), q) });
+            }
+        }
+        let hhat = g_inv_vec(&garbage, lp.b, q);
+        let u2 = mats.C.mul_vec(&hhat, q);
+        fs.absorb_polyvec(&u2);
+
+        // amortize
+        let c = fs.challenge_vec(r, q, TAU1, TAU2);
+        let z = compute_z(&s, &c, q).expect("r chunks of equal length");
+
+        rel = next_relation(lp, q, mats, r, chunk, RoundOut { phi: &phi, b: &b, c: &c, u1: &u1, u2: &u2 }, rel.beta_sq)
+            .map_err(|e| ProofError::Round { round: k, err: Box::new(ProofError::Relation(e)) })?;
+        Z = z;
+        Z.extend(that);
+        Z.extend(hhat);
+        rounds.push(LabradorRound { u1, jl, u2 });
+    }
+    Ok(LabradorProof { rounds, base: Z })
+}
+
+/// Verify a `labrador_prove` proof: replay each round's checks, then check the
+/// base-case witness against the final relation.
+pub fn labrador_verify(lp: &LabradorParams, rel: &R1, proof: &LabradorProof) -> Result<(), ProofError> {
+    let q = &rel.q;
+    let mut fs = labrador_fs(rel);
+    let lifts = jl_lifts(q);
+    let schedule = labrador_schedule(lp, rel.P.cols, q);
+    if schedule.len() != proof.rounds.len() {
+        return Err(ProofError::Rounds { expected: schedule.len(), got: proof.rounds.len() });
+    }
+    let mut rel = rel.clone();
+
+    for (k, ((r, chunk), rd)) in schedule.into_iter().zip(&proof.rounds).enumerate() {
+        let len = rel.P.cols;
+        let in_round = |err| ProofError::Round { round: k, err: Box::new(err) };
+        for (what, got) in [("u1", rd.u1.len()), ("u2", rd.u2.len())] {
+            if got != lp.kappa1 { return Err(in_round(ProofError::Dimension { what, expected: lp.kappa1, got })); }
+        }
+        let mats = round_mats(lp, q, k, r, chunk);
+        fs.absorb_polyvec(&rd.u1);
+
+        // ‖p‖ bound and ct(b''_k) = Σ_j ω_kj p_j
+        let lift_rows = jl_verify(&lp.jl, &mut fs, len, rel.beta_sq, &rd.jl, q).map_err(in_round)?;
+
+        let alpha = fs.alphas(rel.P.rows + lifts, q);
+        let (phi, b) = fold_rows(&rel, &lift_rows, &rd.jl.b_lift, &alpha, q);
+        fs.absorb_polyvec(&rd.u2);
+        let c = fs.challenge_vec(r, q, TAU1, TAU2);
+
+        rel = next_relation(lp, q, mats, r, chunk, RoundOut { phi: &phi, b: &b, c: &c, u1: &rd.u1, u2: &rd.u2 }, rel.beta_sq)
+            .map_err(|e| in_round(ProofError::Relation(e)))?;
+    }
+    rel.check(&proof.base).map_err(ProofError::Base)
+}
+
+// ===================== Exact ℓ2 norm: ‖Z‖₂² ≤ β² =====================
+//
+// ct(σ^{-1}(a)·a) = ⟨coeffs(a), coeffs(a)⟩, so with Z split into chunks s_i and
+// garbage g_ij = ⟨σ^{-1}(s_i), s_j⟩ we get ‖Z‖² = Σ_i ct(g_ii). The prover commits
+// t_i = A s_i and u_g = D \hat g, then answers challenges c with z = Σ c_i s_i; the
+// verifier checks ⟨σ^{-1}(z), z⟩ = Σ_ij σ^{-1}(c_i) c_j g_ij against the opened
+// garbage. Exact as long as the z bound stays below q/2, so ct(g_ii) cannot wrap.
+
+/// Norm-proof parameters (toy ranks).
+#[derive(Clone, Debug)]
+pub struct NormParams {
+    pub kappa: usize,     // A is κ x (chunk length)
+    pub kappa1: usize,    // D is κ1 x (r² δ)
+    pub b: u32,           // decomposition base for \hat g
+    pub r: usize,         // number of chunks
+    pub seed: u64,        // A and D are expanded from this seed
+}
+
+impl Default for NormParams {
+    fn default() -> Self {
+        Self { kappa: 2, kappa1: 2, b: 7, r: 4, seed: 0x4E0 }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NormProof {
+    pub t: Vec<PolyVec>,   // A s_i
+    pub u_g: PolyVec,      // D \hat g
+    pub z: PolyVec,        // Σ c_i s_i
+    pub ghat: PolyVec,     // G^{-1}(g), g row-major r x r
+}
+
+fn norm_mats(np: &NormParams, q: &ModQ, chunk: usize) -> (MatrixRq, MatrixRq) {
+    let delta = digits_for(q, np.b);
+    (expand_block(q, np.kappa, chunk, np.seed, 0), expand_block(q, np.kappa1, np.r * np.r * delta, np.seed, 1))
+}
+
+// ⟨σ^{-1}(a), b⟩ over R_q.
+fn sigma_inner(a: &PolyVec, b: &PolyVec, q: &ModQ) -> Poly {
+    a.iter().zip(b).fold(Poly::zero(), |acc, (x, y)| acc.add(&x.sigma_inv(q).mul(y, q), q))
+}
+
+/// g_ij = ⟨σ^{-1}(s_i), s_j⟩ for all i, j (row-major); ct(g_ii) = ‖s_i‖².
+pub fn norm_garbage(s: &[PolyVec], q: &ModQ) -> PolyVec {
+    s.iter().flat_map(|si| s.iter().map(move |sj| sigma_inner(si, sj, q))).collect()
+}
+
+// ‖c_i s_i‖ ≤ ‖c_i‖₁‖s_i‖, so ‖z‖² ≤ (τ1 + 2τ2)² r β².
+fn norm_z_bound(np: &NormParams, beta_sq: u128) -> u128 {
+    let c_l1 = (TAU1 + 2 * TAU2) as u128;
+    (c_l1 * c_l1 * np.r as u128).saturating_mul(beta_sq)
+}
+
+fn norm_fs(np: &NormParams, len: usize, beta_sq: u128) -> Fs {
+    let mut fs = Fs::new(b"greyhound/norm");
+    fs.absorb_u64(len as u64).absorb_u64(np.r as u64).absorb_bytes(&beta_sq.to_le_bytes());
+    fs
+}
+
+/// Prove ‖Z‖₂² ≤ β² for Z committed chunk-wise as t_i = A s_i.
+pub fn norm_prove(np: &NormParams, q: &ModQ, beta_sq: u128, Z: &PolyVec) -> Result<NormProof, ProofError> {
+    if Z.iter().map(|p| p.l2_sq(q)).sum::<u128>() > beta_sq { return Err(ProofError::Norm("‖Z‖² ≤ β²")); }
+    let chunk = Z.len().div_ceil(np.r);
+    let (A, Dm) = norm_mats(np, q, chunk);
+    let s = chunks_of(Z, np.r, chunk);
+    let mut fs = norm_fs(np, Z.len(), beta_sq);
+
+    let t: Vec<PolyVec> = s.iter().map(|si| A.mul_vec(si, q)).collect();
+    for ti in &t { fs.absorb_polyvec(ti); }
+    let ghat = g_inv_vec(&norm_garbage(&s, q), np.b, q);
+    let u_g = Dm.mul_vec(&ghat, q);
+    fs.absorb_polyvec(&u_g);
+
+    let c = fs.challenge_vec(np.r, q, TAU1, TAU2);
+    let z = compute_z(&s, &c, q).expect("r chunks of equal length");
+    Ok(NormProof { t, u_g, z, ghat })
 }
 
+/// Verify a `norm_prove` proof for a witness of `len` ring elements.
+pub fn norm_verify(np: &NormParams, q: &ModQ, len: usize, beta_sq: u128, proof: &NormProof) -> Result<(), ProofError> {
+    let delta = digits_for(q, np.b);
+    let chunk = len.div_ceil(np.r);
+    let z_bound = norm_z_bound(np, beta_sq);
+    if z_bound >= (q.q / 2) as u128 { return Err(ProofError::Norm("z bound not below q/2")); }
+    let t_bad = proof.t.iter().find(|ti| ti.len() != np.kappa).map_or(np.kappa, |ti| ti.len());
+    for (what, expected, got) in [("t", np.r, proof.t.len()), ("t_i", np.kappa, t_bad), ("u_g", np.kappa1, proof.u_g.len()),
+                                  ("z", chunk, proof.z.len()), ("ghat", np.r * np.r * delta, proof.ghat.len())] {
+        if got != expected { return Err(ProofError::Dimension { what, expected, got }); }
+    }
+    let (A, Dm) = norm_mats(np, q, chunk);
+    let mut fs = norm_fs(np, len, beta_sq);
+    for ti in &proof.t { fs.absorb_polyvec(ti); }
+    fs.absorb_polyvec(&proof.u_g);
+    let c = fs.challenge_vec(np.r, q, TAU1, TAU2);
+
+    // opening of u_g with short digits
+    if Dm.mul_vec(&proof.ghat, q) != proof.u_g { return Err(ProofError::Norm("D ĝ = u_g")); }
+    if proof.ghat.iter().any(|p| p.linf(q) > (np.b / 2) as u64) { return Err(ProofError::Norm("ĝ digit bound")); }
+    let g = greyhound_gadget::g_fwd_vec(&proof.ghat, np.r * np.r, np.b, q);
+
+    // A z = Σ c_i t_i, ‖z‖ bound
+    let mut ct = vec![Poly::zero(); np.kappa];
+    for (ci, ti) in c.iter().zip(&proof.t) {
+        for k in 0..np.kappa { ct[k] = ct[k].add(&ci.mul(&ti[k], q), q); }
+    }
+    if A.mul_vec(&proof.z, q) != ct { return Err(ProofError::Norm("A z = Σ c_i t_i")); }
+    if proof.z.iter().map(|p| p.l2_sq(q)).sum::<u128>() > z_bound { return Err(ProofError::Norm("‖z‖ bound")); }
+
+    // ⟨σ^{-1}(z), z⟩ = Σ_ij σ^{-1}(c_i) c_j g_ij
+    let mut rhs = Poly::zero();
+    for i in 0..np.r {
+        let ci = c[i].sigma_inv(q);
+        for j in 0..np.r { rhs = rhs.add(&ci.mul(&c[j], q).mul(&g[i * np.r + j], q), q); }
+    }
+    if sigma_inner(&proof.z, &proof.z, q) != rhs { return Err(ProofError::Norm("⟨σ^{-1}(z), z⟩ = Σ σ^{-1}(c_i) c_j g_ij")); }
+
+    // ‖Z‖² = Σ_i ct(g_ii)
+    if (0..np.r).map(|i| g[i * np.r + i].ct() as u128).sum::<u128>() > beta_sq {
+        return Err(ProofError::Norm("Σ ct(g_ii) ≤ β²"));
+    }
+    Ok(())
+}
+
+/// ℓ2 bound on the Eq. (14) witness: Eq. (3) plus the \hat l digits (capped at
+/// q/2 per coefficient), the ternary r_v and the hiding randomness r.
+pub fn eq14_norm_bound(B: &HvzkBuilders) -> u128 {
+    let pp = B.pp;
+    let half_q = (pp.q.q / 2) as u128;
+    let half1 = ((pp.b1 / 2) as u128).min(half_q);
+    let lhat = (B.L * pp.delta1) as u128 * half1 * half1;
+    let rv = B.E0.cols as u128; // ternary, like r
+    let mut bound = eq3_norm_bound(pp) + (lhat + rv) * D as u128 + pp.r_bound_sq;
+    if let Some(mask) = &B.z_mask {
+        // swap the ‖S c‖ term of z for the accepted-z bound
+        let z_len = (pp.delta0 * pp.m * D) as u128;
+        let sc = eq3_z_linf(pp) as u128;
+        let zi = (mask.z_linf_bound(pp) as u128).min(half_q);
+        bound = bound - z_len * sc * sc + z_len * zi * zi;
+    }
+    bound
+}
+
+fn eq3_z_linf(pp: &CommitParams) -> u32 {
+    eq3_linf_bounds(pp).iter().find(|&&(b, _)| b == Block::Z).map_or(0, |&(_, v)| v)
+}
+
+/// ℓ∞ bound per Eq. (14) witness block: the Eq. (3) blocks (z as accepted under
+/// `B.z_mask`), the \hat l digits, and ternary r_v and r.
+pub fn eq14_linf_bounds(B: &HvzkBuilders) -> Vec<(Block, u32)> {
+    let pp = B.pp;
+    let mut bounds = eq3_linf_bounds(pp);
+    if let Some(mask) = &B.z_mask {
+        let zi = mask.z_linf_bound(pp).min((pp.q.q / 2) as u64) as u32;
+        for (b, v) in bounds.iter_mut() { if *b == Block::Z { *v = zi; } }
+    }
+    bounds.extend([(Block::Lhat, (pp.b1 / 2).min(pp.q.q / 2)), (Block::Rv, 1), (Block::R, 1)]);
+    bounds
+}
+
+/// ℓ∞ bound per Eq. (3) witness block, with the same per-coefficient bounds as
+/// `eq3_norm_bound` (capped at (q−1)/2, where the bound is vacuous).
+pub fn eq3_linf_bounds(pp: &CommitParams) -> Vec<(Block, u32)> {
+    batch_linf_bounds(pp, 1)
+}
+
+/// `eq3_linf_bounds` for a batch whose widest fold is `fold` (see `BatchGroup`):
+/// folded \hat t and tiers are `fold` times their digit bound, and so is z.
+pub fn batch_linf_bounds(pp: &CommitParams, fold: u32) -> Vec<(Block, u32)> {
+    let cap = |x: u128| x.min((pp.q.q / 2) as u128) as u32;
+    let fold = fold as u128;
+    let half1 = (pp.b1 / 2) as u128;
+    let tiers = pp.tiers.iter().map(|t| cap(fold * (t.b / 2) as u128)).max().unwrap_or(0);
+    let s_inf = match pp.msg_bound { Some(beta) => beta as u128, None => (pp.b0 / 2) as u128 };
+    let z_inf = cap(fold * pp.r as u128 * (TAU1 + 2 * TAU2) as u128 * s_inf);
+    vec![(Block::What, cap(half1)), (Block::That, cap(fold * half1)), (Block::Tiers, tiers), (Block::Z, z_inf)]
+}
+
+/// ℓ2 bound on the Eq. (3) witness Z = [\hat w | \hat t | tiers | z]: digits are at
+/// most ⌊b1/2⌋ (tiers: ⌊b/2⌋), and z = Σ c_i s_i has coefficients at most
+/// r (τ1 + 2τ2) ‖s_i‖∞; every coefficient is capped at q/2.
+pub fn eq3_norm_bound(pp: &CommitParams) -> u128 {
+    batch_norm_bound(pp, &[1])
+}
+
+/// `eq3_norm_bound` for a batch with one folded group per entry of `folds`: each
+/// group has its own \hat w, \hat t, tiers and z, the last three scaled by its fold.
+pub fn batch_norm_bound(pp: &CommitParams, folds: &[u32]) -> u128 {
+    let cap = |x: u128| x.min((pp.q.q / 2) as u128);
+    let half1 = (pp.b1 / 2) as u128;
+    let s_inf = match pp.msg_bound { Some(beta) => beta as u128, None => (pp.b0 / 2) as u128 };
+    let z_len = (pp.delta0 * pp.m) as u128;
+    let nodes = pp.level_nodes();
+    let group = |fold: u128| {
+        let (w, t) = (cap(half1), cap(fold * half1));
+        let mut sq = (pp.delta1 * pp.r) as u128 * w * w + (pp.n * pp.delta1 * pp.r) as u128 * t * t;
+        for (j, tier) in pp.tiers.iter().enumerate() {
+            let ht = cap(fold * (tier.b / 2) as u128);
+            sq += (nodes[j + 1] * pp.n * tier.delta) as u128 * ht * ht;
+        }
+        let z_inf = cap(fold * pp.r as u128 * (TAU1 + 2 * TAU2) as u128 * s_inf);
+        sq + z_len * z_inf * z_inf
+    };
+    folds.iter().map(|&f| group(f as u128)).sum::<u128>() * D as u128
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use greyhound_commit::{commit, CommitParams, Commitment};
-    use rand::{Rng, rngs::StdRng};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    // q for the norm and z-mask tests: large enough that the z bound stays below q/2
+    const Q: ModQ = ModQ { q: 4_294_967_291 };
 
     #[test]
     fn eq3_is_satisfied() {
@@ -290,7 +1857,7 @@ mod tests {
         }
 
         // commit → u, s, \hat t
-        let Commitment { u, dec } = commit(&pp, &f_cols);
+        let Commitment { u, dec } = commit(&pp, &f_cols).unwrap();
 
         // random a (δ0 m) and b (r)
         let mut a = Vec::with_capacity(pp.delta0 * m);
@@ -306,7 +1873,7 @@ mod tests {
 
         // w, \hat w, v with random D
         let Dm = MatrixRq::random(n, pp.delta1 * r, &q, &mut rng);
-        let w  = compute_w(&a, &dec.s, &q);
+        let w  = compute_w(&a, &dec.s, &q).unwrap();
         let (what, v) = derive_w_hat_and_v(&pp, &Dm, &w);
 
         // FS challenge c
@@ -315,20 +1882,354 @@ mod tests {
         let c = sample_challenge(&fs, &pp);
 
         // z and y
-        let z = compute_z(&dec.s, &c, &q);
+        let z = compute_z(&dec.s, &c, &q).unwrap();
         let mut y = Poly::zero();
         for i in 0..r { y = y.add(&w[i].mul(&b[i], &q), &q); }
 
         // build P,h and verify P*[\hat w || \hat t || z] = h
-        let proto = ProtoParams { commit: &pp, D: Dm };
-        let (P, h) = build_linear_system(&proto, &a, &b, &u, &v, &y, &c);
+        let proto = ProtoParams { commit: &pp, D: &Dm };
+        let rel = build_linear_system(&proto, &a, &b, &u, &v, &y, &c).unwrap();
 
         let mut Z: PolyVec = Vec::new();
         Z.extend_from_slice(&what);
         Z.extend_from_slice(&dec.that);
         Z.extend_from_slice(&z);
 
-        let lhs = P.mul_vec(&Z, &q);
-        assert_eq!(lhs, h);
+        assert_eq!(rel.check(&Z), Ok(()));
+    }
+
+    #[test]
+    fn eq3_is_satisfied_short_messages() {
+        let q = ModQ { q: 229 };
+        let (n, m, r) = (2usize, 3usize, 2usize);
+        let pp = CommitParams::gen(q, n, m, r, 6, 7, 7).with_short_messages(8, 3).unwrap();
+        let mut rng = StdRng::seed_from_u64(14);
+        let mut rand_poly = |bound: i64| {
+            let c = std::array::from_fn(|_| rng.gen_range(-bound..=bound).rem_euclid(q.q as i64) as u32);
+            Poly { c }
+        };
+        let f_cols: Vec<PolyVec> = (0..r).map(|_| (0..m).map(|_| rand_poly(8)).collect()).collect();
+        let Commitment { u, dec } = commit(&pp, &f_cols).unwrap();
+
+        let a0: PolyVec = (0..m).map(|_| rand_poly(114)).collect();
+        let a = message_row(&pp, &a0, &q);
+        assert_eq!(a.len(), m);
+        let b: PolyVec = (0..r).map(|_| rand_poly(114)).collect();
+        let Dm = MatrixRq::random(n, pp.delta1 * r, &q, &mut StdRng::seed_from_u64(2));
+
+        let w = compute_w(&a, &dec.s, &q).unwrap();
+        let (what, v) = derive_w_hat_and_v(&pp, &Dm, &w);
+        let mut fs = Fs::new(b"eq3-short");
+        fs.absorb_polyvec(&v).absorb_polyvec(&u);
+        let c = sample_challenge(&fs, &pp);
+        let z = compute_z(&dec.s, &c, &q).unwrap();
+        assert_eq!(z.len(), m);
+        let mut y = Poly::zero();
+        for i in 0..r { y = y.add(&w[i].mul(&b[i], &q), &q); }
+
+        let proto = ProtoParams { commit: &pp, D: &Dm };
+        let rel = build_linear_system(&proto, &a, &b, &u, &v, &y, &c).unwrap();
+        let mut Z: PolyVec = what;
+        Z.extend_from_slice(&dec.that);
+        Z.extend_from_slice(&z);
+        assert_eq!(rel.check(&Z), Ok(()));
+    }
+
+    #[test]
+    fn eq3_is_satisfied_with_tiers() {
+        let q = ModQ { q: 229 };
+        let (n, m, r) = (2usize, 2usize, 5usize);
+        let pp = CommitParams::gen(q, n, m, r, 6, 7, 7).with_depth(3, 7).unwrap();
+        let mut rng = StdRng::seed_from_u64(10);
+        let mut rand_poly = || Poly { c: std::array::from_fn(|_| rng.gen::<u32>() % q.q) };
+
+        let f_cols: Vec<PolyVec> = (0..r).map(|_| (0..m).map(|_| rand_poly()).collect()).collect();
+        let Commitment { u, dec } = commit(&pp, &f_cols).unwrap();
+        let a: PolyVec = (0..pp.delta0 * m).map(|_| rand_poly()).collect();
+        let b: PolyVec = (0..r).map(|_| rand_poly()).collect();
+        let Dm = MatrixRq::random(n, pp.delta1 * r, &q, &mut StdRng::seed_from_u64(1));
+
+        let w = compute_w(&a, &dec.s, &q).unwrap();
+        let (what, v) = derive_w_hat_and_v(&pp, &Dm, &w);
+        let mut fs = Fs::new(b"eq3-tiers");
+        fs.absorb_polyvec(&v).absorb_polyvec(&u);
+        let c = sample_challenge(&fs, &pp);
+        let z = compute_z(&dec.s, &c, &q).unwrap();
+        let mut y = Poly::zero();
+        for i in 0..r { y = y.add(&w[i].mul(&b[i], &q), &q); }
+
+        let proto = ProtoParams { commit: &pp, D: &Dm };
+        let rel = build_linear_system(&proto, &a, &b, &u, &v, &y, &c).unwrap();
+        let mut Z: PolyVec = what;
+        Z.extend_from_slice(&dec.that);
+        for t in &dec.tiers { Z.extend_from_slice(t); }
+        Z.extend_from_slice(&z);
+        assert_eq!(rel.check(&Z), Ok(()));
+        assert_eq!(rel.layout.range(Block::Tiers).len(), pp.tier_witness_len());
+        assert_eq!(&Z[rel.layout.range(Block::That)], &dec.that[..]);
+
+        // dump / reload
+        let bytes = rel.to_bytes();
+        let back = R1::from_bytes(&bytes).unwrap();
+        assert_eq!(back.to_bytes(), bytes);
+        assert_eq!(back.check(&Z), Ok(()));
+        assert!(matches!(R1::from_bytes(&bytes[..bytes.len() - 1]), Err(R1Error::Decode(DecodeError::Truncated))));
+        // the blocks survive, so the reloaded P absorbs like the original
+        let digest = |r: &R1| { let mut fs = Fs::new(b"r1-absorb"); r.P.absorb(&mut fs); fs.digest() };
+        assert_eq!(back.P.blocks().len(), rel.P.blocks().len());
+        assert_eq!(digest(&back), digest(&rel));
+        // moving the first block below the last row is caught before `push` asserts
+        let mut far = bytes.clone();
+        let at = 4 + 1 + 4 + 4 + 4 + 16 + 4 + 5 * rel.layout.blocks().len() + 4;
+        far[at..at + 4].copy_from_slice(&(rel.P.rows as u32).to_le_bytes());
+        assert_eq!(R1::from_bytes(&far).err(), Some(R1Error::Decode(DecodeError::Invalid("block of P out of range"))));
+
+        // a wrong witness is reported by row / norm
+        let mut bad = Z.clone();
+        bad[rel.layout.offset(Block::Z)] = bad[rel.layout.offset(Block::Z)].add(&Poly::monomial(0, 1, &q), &q);
+        assert!(matches!(rel.check(&bad), Err(R1Error::Row { block: "c·G·what − a·z", .. })));
+        let tight = R1 { beta_sq: 0, ..rel };
+        assert!(matches!(tight.check(&Z), Err(R1Error::Norm { .. })));
+    }
+
+    #[test]
+    fn sparse_p_matches_dense() {
+        let q = ModQ { q: 229 };
+        let (n, m, r) = (2usize, 2usize, 5usize);
+        let pp = CommitParams::gen(q, n, m, r, 6, 7, 4).with_depth(3, 4).unwrap();
+        let mut rng = StdRng::seed_from_u64(12);
+        let mut rand_poly = || Poly { c: std::array::from_fn(|_| rng.gen::<u32>() % q.q) };
+        let a: PolyVec = (0..pp.delta0 * m).map(|_| rand_poly()).collect();
+        let b: PolyVec = (0..r).map(|_| rand_poly()).collect();
+        let c: PolyVec = (0..r).map(|_| rand_poly()).collect();
+        let u: PolyVec = (0..n).map(|_| rand_poly()).collect();
+        let v: PolyVec = (0..n).map(|_| rand_poly()).collect();
+        let y = rand_poly();
+        let Dm = MatrixRq::random(n, pp.delta1 * r, &q, &mut StdRng::seed_from_u64(1));
+        let rel = build_linear_system(&ProtoParams { commit: &pp, D: &Dm }, &a, &b, &u, &v, &y, &c).unwrap();
+
+        let dense = rel.P.to_dense(&q);
+        let x: PolyVec = (0..rel.P.cols).map(|_| rand_poly()).collect();
+        let yv: PolyVec = (0..rel.P.rows).map(|_| rand_poly()).collect();
+        assert_eq!(rel.P.mul_vec(&x, &q), dense.mul_vec(&x, &q));
+        // ⟨y, P x⟩ = ⟨P^T y, x⟩
+        let lhs = yv.iter().zip(rel.P.mul_vec(&x, &q)).fold(Poly::zero(), |acc, (a, b)| acc.add(&a.mul(&b, &q), &q));
+        let rhs = rel.P.mul_transpose(&yv, &q).iter().zip(&x).fold(Poly::zero(), |acc, (a, b)| acc.add(&a.mul(b, &q), &q));
+        assert_eq!(lhs, rhs);
+        // row fold at α agrees with the dense matrix
+        let e = Ext4::new(q);
+        let alpha = Fq4 { c: [3, 141, 59, 26] };
+        let wts: Vec<Fq4> = (0..rel.P.rows).map(|i| Fq4 { c: [i as u32 + 1, 7, 0, i as u32 % 5] }).collect();
+        let folded = rel.P.fold_rows_ext(&wts, &alpha, &e);
+        for (k, got) in folded.iter().enumerate() {
+            let want = (0..rel.P.rows).fold(Fq4::zero(), |acc, i| e.add(&acc, &e.mul(&wts[i], &dense.at(i, k).eval_ext(&alpha, &e))));
+            assert_eq!(*got, want);
+        }
+        // only a handful of blocks, no dense copy of A, B or D
+        assert!(rel.P.blocks().len() < 16);
+
+        // short inputs and misplaced blocks are errors, not panics
+        let short = &v[..n - 1].to_vec();
+        assert_eq!(build_linear_system(&ProtoParams { commit: &pp, D: &Dm }, &a, &b, &u, short, &y, &c).err(),
+                   Some(R1Error::Dimension { what: "v", expected: n, got: n - 1 }));
+        let mut P = SparseP::new(2, 2);
+        assert_eq!(P.push(1, 0, PBlock::mat(&Dm)), Err(R1Error::Block { row: 1, col: 0 }));
+        assert_eq!(P.push(usize::MAX, 0, PBlock::Row(vec![Poly::zero()])), Err(R1Error::Block { row: usize::MAX, col: 0 }));
+        assert_eq!(Layout::new().with(Block::Z, 1).unwrap().with(Block::Z, 2), Err(R1Error::DuplicateBlock(Block::Z)));
+    }
+
+    // ---- LaBRADOR ----
+
+    fn small_relation(rows: usize, len: usize, seed: u64, q: &ModQ) -> (R1<'static>, PolyVec) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let P = MatrixRq::random(rows, len, q, &mut rng);
+        let Z: PolyVec = (0..len).map(|_| {
+            Poly { c: std::array::from_fn(|_| [0, 1, q.q - 1][rng.gen_range(0..3)]) }
+        }).collect();
+        let h = P.mul_vec(&Z, q);
+        let beta_sq = Z.iter().map(|p| p.l2_sq(q)).sum();
+        (R1 { q: *q, P: P.into(), h, beta_sq, layout: Layout::new().with(Block::Z, len).unwrap(), row_blocks: Vec::new() }, Z)
+    }
+
+    #[test]
+    fn labrador_recursive_roundtrip() {
+        let q = ModQ { q: 229 };
+        let (rel, Z) = small_relation(3, 120, 1, &q);
+        let lp = LabradorParams { base_len: 40, ..Default::default() };
+        let proof = labrador_prove(&lp, &rel, Z).unwrap();
+        assert!(proof.rounds.len() >= 2);
+        assert!(proof.base.len() < 120);
+        assert_eq!(labrador_verify(&lp, &rel, &proof), Ok(()));
+
+        let (rel, Z) = small_relation(3, 120, 1, &q);
+        assert_eq!(labrador_prove(&lp, &rel, Z[1..].to_vec()).err(),
+                   Some(ProofError::Dimension { what: "Z", expected: 120, got: 119 }));
+    }
+
+    #[test]
+    fn jl_standalone_roundtrip() {
+        let q = ModQ { q: 229 };
+        let (rel, Z) = small_relation(1, 12, 3, &q);
+        let jp = JlParams { slack: 2 };
+        let mut fs = Fs::new(b"test/jl");
+        let (proof, rows) = jl_prove(&jp, &mut fs, &Z, rel.beta_sq, &q);
+        for (row, bl) in rows.iter().zip(&proof.b_lift) { assert_eq!(&inner(row, &Z, &q), bl); }
+        assert!(jp.proven_bound(rel.beta_sq) >= rel.beta_sq);
+        // ‖p‖² ≤ 128·30 only proves ‖Z‖² ≤ 128 (+1 for rounding)
+        assert_eq!(JlParams::default().proven_bound(30), 129);
+
+        let mut fs = Fs::new(b"test/jl");
+        assert_eq!(jl_verify(&jp, &mut fs, Z.len(), rel.beta_sq, &proof, &q), Ok(rows));
+        let mut bad = proof.clone();
+        bad.b_lift[0] = bad.b_lift[0].add(&Poly::monomial(0, 1, &q), &q);
+        assert_eq!(jl_verify(&jp, &mut Fs::new(b"test/jl"), Z.len(), rel.beta_sq, &bad, &q).err(), Some(ProofError::JlLift { lift: 0 }));
+        // a projection longer than the slack allows is refused outright
+        let mut big = proof.clone();
+        big.p[0] = 1 << 20;
+        assert!(matches!(jl_verify(&jp, &mut Fs::new(b"test/jl"), Z.len(), rel.beta_sq, &big, &q), Err(ProofError::JlNorm { .. })));
+    }
+
+    #[test]
+    fn jl_rejects_overflowing_projection() {
+        // four i64::MIN entries sum to 2^128 in ‖p‖², which used to wrap to 0
+        let q = ModQ { q: 229 };
+        let (rel, Z) = small_relation(1, 12, 3, &q);
+        let jp = JlParams { slack: 2 };
+        let (mut hostile, _) = jl_prove(&jp, &mut Fs::new(b"test/jl"), &Z, rel.beta_sq, &q);
+        hostile.p = vec![0; JL_ROWS];
+        hostile.p[..4].fill(i64::MIN);
+        assert_eq!(norm_sq_i64(&hostile.p), None);
+        for beta_sq in [rel.beta_sq, u128::MAX] {
+            assert_eq!(jl_verify(&jp, &mut Fs::new(b"test/jl"), Z.len(), beta_sq, &hostile, &q).err(),
+                       Some(ProofError::JlNorm { norm_sq: u128::MAX, bound: jp.p_bound(beta_sq) }));
+        }
+    }
+
+    #[test]
+    fn labrador_rejects_tampering() {
+        let q = ModQ { q: 229 };
+        let (rel, Z) = small_relation(2, 80, 2, &q);
+        let lp = LabradorParams::default();
+        let proof = labrador_prove(&lp, &rel, Z).unwrap();
+        assert_eq!(labrador_verify(&lp, &rel, &proof), Ok(()));
+
+        let mut bad = rel.clone();
+        bad.h[0] = bad.h[0].add(&Poly::monomial(0, 1, &q), &q);
+        assert!(labrador_verify(&lp, &bad, &proof).is_err());
+
+        let mut p2 = proof.clone();
+        p2.base[0] = p2.base[0].add(&Poly::monomial(3, 1, &q), &q);
+        assert!(matches!(labrador_verify(&lp, &rel, &p2), Err(ProofError::Base(_))));
+
+        let mut p3 = proof.clone();
+        p3.rounds[0].jl.p[0] += 1;
+        assert!(matches!(labrador_verify(&lp, &rel, &p3), Err(ProofError::Round { round: 0, .. })));
+
+        let mut p4 = proof.clone();
+        p4.rounds.pop();
+        assert_eq!(labrador_verify(&lp, &rel, &p4), Err(ProofError::Rounds { expected: proof.rounds.len(), got: proof.rounds.len() - 1 }));
+    }
+
+    #[test]
+    fn labrador_base_case_only() {
+        let q = ModQ { q: 229 };
+        let (rel, Z) = small_relation(2, 10, 3, &q);
+        let lp = LabradorParams::default();
+        let proof = labrador_prove(&lp, &rel, Z.clone()).unwrap();
+        assert!(proof.rounds.is_empty());
+        assert_eq!(proof.base, Z);
+        assert_eq!(labrador_verify(&lp, &rel, &proof), Ok(()));
+    }
+
+    // ---- exact ℓ2-norm proof ----
+
+    fn ternary(len: usize, seed: u64) -> PolyVec {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len).map(|_| {
+            Poly { c: std::array::from_fn(|_| [0, 1, Q.q - 1][rng.gen_range(0..3)]) }
+        }).collect()
+    }
+
+    #[test]
+    fn ct_sigma_inv_is_norm() {
+        let Z = ternary(3, 7);
+        let g = norm_garbage(std::slice::from_ref(&Z), &Q);
+        assert_eq!(g[0].ct() as u128, Z.iter().map(|p| p.l2_sq(&Q)).sum::<u128>());
+    }
+
+    #[test]
+    fn norm_roundtrip_and_tampering() {
+        let np = NormParams::default();
+        let Z = ternary(10, 8);
+        let norm: u128 = Z.iter().map(|p| p.l2_sq(&Q)).sum();
+        let proof = norm_prove(&np, &Q, norm, &Z).unwrap();
+        assert_eq!(norm_verify(&np, &Q, Z.len(), norm, &proof), Ok(()));
+
+        // exact: one less than the true norm is rejected, and not proven
+        assert!(norm_verify(&np, &Q, Z.len(), norm - 1, &proof).is_err());
+        assert_eq!(norm_prove(&np, &Q, norm - 1, &Z), Err(ProofError::Norm("‖Z‖² ≤ β²")));
+
+        let mut bad = proof.clone();
+        bad.z[0] = bad.z[0].add(&Poly::monomial(1, 1, &Q), &Q);
+        assert!(norm_verify(&np, &Q, Z.len(), norm, &bad).is_err());
+
+        let mut bad = proof.clone();
+        bad.ghat[0] = bad.ghat[0].add(&Poly::monomial(0, 1, &Q), &Q);
+        assert!(norm_verify(&np, &Q, Z.len(), norm, &bad).is_err());
+    }
+
+    // ---- masked z ----
+
+    fn witness(pp: &CommitParams, seed: u64) -> (Vec<PolyVec>, Vec<Poly>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut small = || Poly { c: std::array::from_fn(|_| (rng.gen_range(-2i64..=2)).rem_euclid(Q.q as i64) as u32) };
+        let ell = pp.delta0 * pp.m;
+        let s = (0..pp.r).map(|_| (0..ell).map(|_| small()).collect()).collect();
+        let a = (0..ell).map(|_| small()).collect();
+        (s, a)
+    }
+
+    fn check_masked(pp: &CommitParams, mask: &ZMask, seed: u64) {
+        let (s, a) = witness(pp, seed);
+        let fs = Fs::new(b"test/zmask");
+        let (z, msg, c) = compute_z_masked(pp, mask, &a, &s, &fs, &mut StdRng::seed_from_u64(seed)).unwrap();
+        assert_eq!(c, masked_challenge(&fs, pp, &msg));
+        assert!(z.iter().all(|p| p.linf(&Q) <= mask.z_linf_bound(pp)));
+        // A z = t_y + Σ c_i A s_i and a^T z = w_y + Σ c_i a^T s_i
+        let dot = |u: &[Poly], v: &[Poly]| u.iter().zip(v).fold(Poly::zero(), |acc, (x, y)| acc.add(&x.mul(y, &Q), &Q));
+        let mut t = msg.t_y.clone();
+        let mut w = msg.w_y.clone();
+        for (si, ci) in s.iter().zip(&c) {
+            for (tk, ak) in t.iter_mut().zip(pp.A.mul_vec(si, &Q)) { *tk = tk.add(&ak.mul(ci, &Q), &Q); }
+            w = w.add(&dot(&a, si).mul(ci, &Q), &Q);
+        }
+        assert_eq!(pp.A.mul_vec(&z, &Q), t);
+        assert_eq!(dot(&a, &z), w);
+        assert!(msg.attempt < MAX_Z_ATTEMPTS);
+    }
+
+    #[test]
+    fn uniform_mask_calculators_and_opening() {
+        let pp = CommitParams::gen(Q, 1, 2, 2, 4, 4, 3);
+        let mask = ZMask::uniform_for(&pp, 3.0).unwrap();
+        let reps = mask.expected_repetitions(&pp);
+        assert!(reps > 2.5 && reps < 3.5, "{}", reps);
+        assert!(ZMask::Uniform { gamma: sc_linf_bound(&pp) as u32 }.expected_repetitions(&pp).is_infinite());
+        // toy q leaves no room for the mask
+        assert_eq!(ZMask::uniform_for(&CommitParams::gen(ModQ { q: 229 }, 1, 2, 2, 4, 4, 3), 3.0), None);
+        assert_eq!(ZMask::uniform_for(&pp, 1.0), None);
+        assert_eq!(ZMask::uniform_for(&pp, f64::NAN), None);
+        check_masked(&pp, &mask, 1);
+    }
+
+    #[test]
+    fn gaussian_mask_opening() {
+        let pp = CommitParams::gen(Q, 1, 2, 2, 4, 4, 3);
+        let mask = ZMask::gaussian_for(&pp, 11.0);
+        let ZMask::Gaussian { sigma, m } = mask else { unreachable!() };
+        assert!((sigma - 11.0 * sc_l2_bound(&pp)).abs() < 1e-6);
+        assert_eq!(mask.expected_repetitions(&pp), m);
+        assert!(mask.z_linf_bound(&pp) < (Q.q / 2) as u64);
+        check_masked(&pp, &mask, 2);
     }
 }
@@ -4,7 +4,7 @@
 pub const D: usize = 64;
 
 /// Modulus wrapper (caller supplies a 32-bit prime; later we’ll pick q ≡ 5 (mod 8)).
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ModQ {
     pub q: u32,
 }
@@ -28,6 +28,86 @@ impl ModQ {
         let x = (a as u64) * (b as u64) % (self.q as u64);
         x as u32
     }
+    /// Centered representative of a in [-(q-1)/2, (q-1)/2].
+    #[inline] pub fn signed(&self, a: u32) -> i64 {
+        let (a, q) = (a as i64, self.q as i64);
+        if a > q / 2 { a - q } else { a }
+    }
+    pub fn pow(&self, mut a: u32, mut e: u64) -> u32 {
+        let mut acc = 1 % self.q;
+        while e > 0 {
+            if e & 1 == 1 { acc = self.mul(acc, a); }
+            a = self.mul(a, a);
+            e >>= 1;
+        }
+        acc
+    }
+    /// Inverse of a ≠ 0 (q prime).
+    #[inline] pub fn inv(&self, a: u32) -> u32 {
+        assert!(a % self.q != 0, "inverse of zero");
+        self.pow(a, self.q as u64 - 2)
+    }
+}
+
+/// F_{q^4} = Z_q[Y]/(Y^4 − η) for a non-square η (needs q ≡ 1 mod 4).
+/// The root crate's tower Fq2 = Fq[u]/(u² − 6), Fq4 = Fq2[v]/(v² − u) is
+/// `with_eta(q, 6)`: (a0 + a1·u) + (b0 + b1·u)·v has c = [a0, b0, a1, b1].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ext4 {
+    pub q: ModQ,
+    pub eta: u32,
+}
+
+/// Element of F_{q^4}, coefficients of 1, Y, Y^2, Y^3 in [0, q).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Fq4 {
+    pub c: [u32; 4],
+}
+
+impl Ext4 {
+    pub fn new(q: ModQ) -> Self {
+        assert_eq!(q.q % 4, 1, "Y^4 - eta is irreducible only for q = 1 mod 4");
+        let eta = (2..q.q).find(|&x| q.pow(x, (q.q as u64 - 1) / 2) == q.q - 1).expect("non-square");
+        Self { q, eta }
+    }
+    /// As `new`, with a given non-square η.
+    pub fn with_eta(q: ModQ, eta: u32) -> Self {
+        assert_eq!(q.q % 4, 1, "Y^4 - eta is irreducible only for q = 1 mod 4");
+        assert_eq!(q.pow(eta, (q.q as u64 - 1) / 2), q.q - 1, "eta must be a non-square");
+        Self { q, eta }
+    }
+    #[inline] pub fn from_base(&self, a: u32) -> Fq4 { Fq4 { c: [a % self.q.q, 0, 0, 0] } }
+    #[inline] pub fn one(&self) -> Fq4 { self.from_base(1) }
+    pub fn add(&self, a: &Fq4, b: &Fq4) -> Fq4 {
+        Fq4 { c: std::array::from_fn(|i| self.q.add(a.c[i], b.c[i])) }
+    }
+    pub fn sub(&self, a: &Fq4, b: &Fq4) -> Fq4 {
+        Fq4 { c: std::array::from_fn(|i| self.q.sub(a.c[i], b.c[i])) }
+    }
+    pub fn neg(&self, a: &Fq4) -> Fq4 {
+        Fq4 { c: std::array::from_fn(|i| self.q.neg(a.c[i])) }
+    }
+    /// Multiply by a base-field scalar.
+    pub fn scale(&self, a: &Fq4, k: u32) -> Fq4 {
+        Fq4 { c: std::array::from_fn(|i| self.q.mul(a.c[i], k)) }
+    }
+    /// Schoolbook product with Y^4 = η.
+    pub fn mul(&self, a: &Fq4, b: &Fq4) -> Fq4 {
+        let qq = self.q.q as u128;
+        let mut acc = [0u128; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                let prod = a.c[i] as u128 * b.c[j] as u128;
+                if i + j < 4 { acc[i + j] += prod; } else { acc[i + j - 4] += prod % qq * self.eta as u128; }
+            }
+        }
+        Fq4 { c: std::array::from_fn(|i| (acc[i] % qq) as u32) }
+    }
+}
+
+impl Fq4 {
+    pub fn zero() -> Self { Self { c: [0; 4] } }
+    pub fn is_zero(&self) -> bool { self.c == [0; 4] }
 }
 
 /// Dense polynomial with coefficients in [0, q), degree < D.
@@ -38,6 +118,7 @@ pub struct Poly {
 
 impl Poly {
     pub fn zero() -> Self { Self { c: [0; D] } }
+    pub fn is_zero(&self) -> bool { self.c.iter().all(|&x| x == 0) }
     pub fn from_coeffs(coeffs: [u32; D], q: &ModQ) -> Self {
         // Normalize into [0,q)
         let mut out = [0u32; D];
@@ -54,6 +135,16 @@ impl Poly {
     }
     #[inline] pub fn ct(&self) -> u32 { self.c[0] } // constant term
 
+    /// ℓ∞ norm of the centered coefficients.
+    pub fn linf(&self, q: &ModQ) -> u64 {
+        self.c.iter().map(|&v| q.signed(v).unsigned_abs()).max().unwrap_or(0)
+    }
+
+    /// Squared ℓ2 norm of the centered coefficients.
+    pub fn l2_sq(&self, q: &ModQ) -> u128 {
+        self.c.iter().map(|&v| { let s = q.signed(v) as i128; (s * s) as u128 }).sum()
+    }
+
     pub fn add(&self, other: &Self, q: &ModQ) -> Self {
         let mut r = [0u32; D];
         for i in 0..D { r[i] = q.add(self.c[i], other.c[i]); }
@@ -101,6 +192,11 @@ impl Poly {
         Self { c: out }
     }
 
+    /// Evaluate the coefficient polynomial (degree < D, over Z_q[X]) at x ∈ F_{q^4}.
+    pub fn eval_ext(&self, x: &Fq4, e: &Ext4) -> Fq4 {
+        self.c.iter().rev().fold(Fq4::zero(), |acc, &c| e.add(&e.mul(&acc, x), &e.from_base(c)))
+    }
+
     /// σ^{-1}: X ↦ X^{-1} in R_q (see §4.1). For a = ∑ a_i X^i:
     /// a(X^{-1}) ≡ a_0 + ∑_{i=1}^{D-1} (-a_i) X^{D-i} (mod X^D+1).
     pub fn sigma_inv(&self, q: &ModQ) -> Self {
@@ -115,6 +211,284 @@ impl Poly {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Poly {
+    fn zeroize(&mut self) { self.c.zeroize(); }
+}
+
+/// Owned secret ring vector (masks, hiding randomness). Deliberately not `Clone`;
+/// with the `zeroize` feature the buffer is wiped before it is freed.
+pub struct SecretPolyVec(Vec<Poly>);
+
+impl SecretPolyVec {
+    pub fn new(v: Vec<Poly>) -> Self { Self(v) }
+
+    /// Append `more` to the secret buffer `dst`. When `dst` has to grow, the new
+    /// buffer is reserved inside a `SecretPolyVec` and filled by copy, so the
+    /// outgrown one is wiped before it is freed (`Vec::append` would free it as is);
+    /// `more` is wiped as well.
+    pub fn append_to(dst: &mut Vec<Poly>, more: Vec<Poly>) {
+        let more = Self::new(more);
+        if dst.capacity() - dst.len() < more.len() {
+            let mut grown = Self::new(Vec::with_capacity(dst.len() + more.len()));
+            grown.extend_from_slice(dst);
+            std::mem::swap(dst, &mut grown.0);
+        }
+        dst.extend_from_slice(&more);
+    }
+}
+
+impl std::ops::Deref for SecretPolyVec {
+    type Target = Vec<Poly>;
+    fn deref(&self) -> &Vec<Poly> { &self.0 }
+}
+
+impl std::ops::DerefMut for SecretPolyVec {
+    fn deref_mut(&mut self) -> &mut Vec<Poly> { &mut self.0 }
+}
+
+impl Drop for SecretPolyVec {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretPolyVec {}
+
+// ===================== Wire encoding =====================
+//
+// Canonical byte format shared by params, commitments and proofs: a 4-byte magic
+// and WIRE_VERSION, then the fields in declaration order. Integers are fixed-width
+// little-endian, sequences carry a u32 length, residues are u32 in [0, q). Every
+// value has exactly one encoding; decoders reject anything else, trailing bytes included.
+
+pub const WIRE_VERSION: u8 = 1;
+
+/// Why `from_bytes` rejected its input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Input ended early.
+    Truncated,
+    /// Bytes left over after the value.
+    Trailing(usize),
+    /// Magic of a different type.
+    Magic,
+    /// Format version this build does not read.
+    Version(u8),
+    /// Unknown enum tag (or a non-0/1 flag) for `what`.
+    Tag { what: &'static str, tag: u8 },
+    /// A residue outside [0, q).
+    Unreduced { value: u32, q: u32 },
+    /// Length of `what` differs from what the parameters imply.
+    Dimension { what: &'static str, expected: usize, got: usize },
+    /// Any other inconsistency.
+    Invalid(&'static str),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "truncated input"),
+            DecodeError::Trailing(n) => write!(f, "{} trailing bytes", n),
+            DecodeError::Magic => write!(f, "wrong magic"),
+            DecodeError::Version(v) => write!(f, "unsupported format version {}", v),
+            DecodeError::Tag { what, tag } => write!(f, "unknown tag {} for {}", tag, what),
+            DecodeError::Unreduced { value, q } => write!(f, "residue {} not reduced mod {}", value, q),
+            DecodeError::Dimension { what, expected, got } =>
+                write!(f, "dimension mismatch in {}: expected {}, got {}", what, expected, got),
+            DecodeError::Invalid(why) => write!(f, "invalid encoding: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Encoder for the wire format; `new` writes magic and version.
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    pub fn new(magic: &[u8; 4]) -> Self {
+        let mut buf = magic.to_vec();
+        buf.push(WIRE_VERSION);
+        Writer(buf)
+    }
+    pub fn put_u8(&mut self, x: u8) -> &mut Self { self.0.push(x); self }
+    pub fn put_u32(&mut self, x: u32) -> &mut Self { self.0.extend_from_slice(&x.to_le_bytes()); self }
+    pub fn put_u64(&mut self, x: u64) -> &mut Self { self.0.extend_from_slice(&x.to_le_bytes()); self }
+    pub fn put_u128(&mut self, x: u128) -> &mut Self { self.0.extend_from_slice(&x.to_le_bytes()); self }
+    /// Lengths and dimensions, as u32.
+    pub fn put_len(&mut self, n: usize) -> &mut Self {
+        self.put_u32(u32::try_from(n).expect("length does not fit the wire format"))
+    }
+    pub fn put_poly(&mut self, p: &Poly) -> &mut Self {
+        for &x in &p.c { self.put_u32(x); }
+        self
+    }
+    pub fn put_polys(&mut self, v: &[Poly]) -> &mut Self {
+        self.put_len(v.len());
+        for p in v { self.put_poly(p); }
+        self
+    }
+    /// Opaque bytes with a length prefix (digests, nested encodings).
+    pub fn put_bytes(&mut self, b: &[u8]) -> &mut Self {
+        self.put_len(b.len());
+        self.0.extend_from_slice(b);
+        self
+    }
+    pub fn put_fq4(&mut self, x: &Fq4) -> &mut Self {
+        for &c in &x.c { self.put_u32(c); }
+        self
+    }
+    pub fn finish(self) -> Vec<u8> { self.0 }
+}
+
+/// Decoder for the wire format. Residues are checked against `q` once it is known
+/// (from the caller's parameters, or read from the input by `get_modulus`).
+pub struct Reader<'a> {
+    buf: &'a [u8],
+    q: Option<ModQ>,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8], magic: &[u8; 4], q: Option<ModQ>) -> Result<Self, DecodeError> {
+        let mut rd = Reader { buf: bytes, q };
+        if rd.take(4)? != magic { return Err(DecodeError::Magic); }
+        match rd.get_u8()? {
+            WIRE_VERSION => Ok(rd),
+            v => Err(DecodeError::Version(v)),
+        }
+    }
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.buf.len() < n { return Err(DecodeError::Truncated); }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+    pub fn get_u8(&mut self) -> Result<u8, DecodeError> { Ok(self.take(1)?[0]) }
+    pub fn get_u32(&mut self) -> Result<u32, DecodeError> { Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap())) }
+    pub fn get_u64(&mut self) -> Result<u64, DecodeError> { Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap())) }
+    pub fn get_u128(&mut self) -> Result<u128, DecodeError> { Ok(u128::from_le_bytes(self.take(16)?.try_into().unwrap())) }
+    pub fn get_bool(&mut self, what: &'static str) -> Result<bool, DecodeError> {
+        match self.get_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(DecodeError::Tag { what, tag }),
+        }
+    }
+    /// A length whose `elem_bytes`-byte elements must still fit in the input.
+    pub fn get_len(&mut self, elem_bytes: usize) -> Result<usize, DecodeError> {
+        let n = self.get_u32()? as usize;
+        if n.saturating_mul(elem_bytes) > self.buf.len() { return Err(DecodeError::Truncated); }
+        Ok(n)
+    }
+    /// Checks that `n` elements of `elem_bytes` bytes still fit in the input, so a
+    /// decoder can size a buffer by a product of untrusted dimensions.
+    pub fn check_fits(&self, n: usize, elem_bytes: usize) -> Result<(), DecodeError> {
+        if n.saturating_mul(elem_bytes) > self.buf.len() { return Err(DecodeError::Truncated); }
+        Ok(())
+    }
+    /// A modulus q ≥ 2; later residues are checked against it.
+    pub fn get_modulus(&mut self) -> Result<ModQ, DecodeError> {
+        let q = ModQ::new(self.get_u32()?);
+        if q.q < 2 { return Err(DecodeError::Invalid("modulus below 2")); }
+        if self.q.is_some_and(|p| p != q) { return Err(DecodeError::Invalid("modulus differs from the parameters")); }
+        self.q = Some(q);
+        Ok(q)
+    }
+    pub fn get_residue(&mut self) -> Result<u32, DecodeError> {
+        let x = self.get_u32()?;
+        match self.q {
+            Some(q) if x >= q.q => Err(DecodeError::Unreduced { value: x, q: q.q }),
+            _ => Ok(x),
+        }
+    }
+    pub fn get_poly(&mut self) -> Result<Poly, DecodeError> {
+        let mut p = Poly::zero();
+        for x in p.c.iter_mut() { *x = self.get_residue()?; }
+        Ok(p)
+    }
+    pub fn get_polys(&mut self) -> Result<Vec<Poly>, DecodeError> {
+        let n = self.get_len(4 * D)?;
+        (0..n).map(|_| self.get_poly()).collect()
+    }
+    /// `get_polys` with the length fixed by the parameters.
+    pub fn get_polys_exact(&mut self, what: &'static str, expected: usize) -> Result<Vec<Poly>, DecodeError> {
+        let v = self.get_polys()?;
+        if v.len() != expected { return Err(DecodeError::Dimension { what, expected, got: v.len() }); }
+        Ok(v)
+    }
+    pub fn get_bytes(&mut self) -> Result<&'a [u8], DecodeError> {
+        let n = self.get_len(1)?;
+        self.take(n)
+    }
+    pub fn get_fq4(&mut self) -> Result<Fq4, DecodeError> {
+        let mut x = Fq4::zero();
+        for c in x.c.iter_mut() { *c = self.get_residue()?; }
+        Ok(x)
+    }
+    pub fn finish(self) -> Result<(), DecodeError> {
+        if self.buf.is_empty() { Ok(()) } else { Err(DecodeError::Trailing(self.buf.len())) }
+    }
+}
+
+const POLY_MAGIC: &[u8; 4] = b"GHPL";
+
+impl Poly {
+    /// Wire encoding of a single ring element.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(POLY_MAGIC);
+        w.put_poly(self);
+        w.finish()
+    }
+    pub fn from_bytes(bytes: &[u8], q: &ModQ) -> Result<Poly, DecodeError> {
+        let mut rd = Reader::new(bytes, POLY_MAGIC, Some(*q))?;
+        let p = rd.get_poly()?;
+        rd.finish()?;
+        Ok(p)
+    }
+}
+
+/// `CanonicalSerialize`/`CanonicalDeserialize` for a type with a self-contained wire
+/// encoding (the parameter sets): the bytes of `$enc` as an ark `Vec<u8>`, decoded and
+/// validated by `$dec`. Commitments and proofs decode against their params instead.
+#[cfg(feature = "ark-serialize")]
+#[macro_export]
+macro_rules! impl_canonical_serialize {
+    ($t:ty, |$s:ident| $enc:expr, |$b:ident| $dec:expr) => {
+        impl $crate::ark_serialize::CanonicalSerialize for $t {
+            fn serialize_with_mode<W: $crate::ark_serialize::Write>(
+                &self, writer: W, compress: $crate::ark_serialize::Compress,
+            ) -> Result<(), $crate::ark_serialize::SerializationError> {
+                let $s = self;
+                let bytes: Vec<u8> = $enc;
+                $crate::ark_serialize::CanonicalSerialize::serialize_with_mode(&bytes, writer, compress)
+            }
+            fn serialized_size(&self, compress: $crate::ark_serialize::Compress) -> usize {
+                let $s = self;
+                let bytes: Vec<u8> = $enc;
+                $crate::ark_serialize::CanonicalSerialize::serialized_size(&bytes, compress)
+            }
+        }
+        impl $crate::ark_serialize::Valid for $t {
+            fn check(&self) -> Result<(), $crate::ark_serialize::SerializationError> { Ok(()) }
+        }
+        impl $crate::ark_serialize::CanonicalDeserialize for $t {
+            fn deserialize_with_mode<R: $crate::ark_serialize::Read>(
+                reader: R, compress: $crate::ark_serialize::Compress, validate: $crate::ark_serialize::Validate,
+            ) -> Result<Self, $crate::ark_serialize::SerializationError> {
+                let bytes = <Vec<u8> as $crate::ark_serialize::CanonicalDeserialize>::deserialize_with_mode(reader, compress, validate)?;
+                let $b: &[u8] = &bytes;
+                $dec.map_err(|_| $crate::ark_serialize::SerializationError::InvalidData)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "ark-serialize")]
+pub use ark_serialize;
+
 // ---- simple tests (run `cargo test -p greyhound-ring`) ----
 #[cfg(test)]
 mod tests {
@@ -145,6 +519,42 @@ mod tests {
         assert_eq!(prod, one.neg(&q));
     }
 
+    #[test]
+    fn centered_norms() {
+        let q = ModQ::new(229);
+        let mut a = Poly::zero();
+        a.c[0] = 3; a.c[5] = 228; a.c[9] = 115; // 3, -1, -114
+        assert_eq!(q.signed(114), 114);
+        assert_eq!(q.signed(115), -114);
+        assert_eq!(a.linf(&q), 114);
+        assert_eq!(a.l2_sq(&q), 9 + 1 + 114 * 114);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn poly_zeroize() {
+        use zeroize::Zeroize;
+        let mut p = Poly::monomial(3, 7, &ModQ::new(229));
+        p.zeroize();
+        assert!(p.is_zero());
+    }
+
+    #[test]
+    fn ext4_field_axioms() {
+        let q = ModQ::new(229);
+        let e = Ext4::new(q);
+        assert_eq!(q.mul(3, q.inv(3)), 1);
+        // Y^4 = η, and (Y^3)·Y = η
+        let y = Fq4 { c: [0, 1, 0, 0] };
+        let y3 = Fq4 { c: [0, 0, 0, 1] };
+        assert_eq!(e.mul(&y3, &y), e.from_base(e.eta));
+        // evaluation is multiplicative while the product does not wrap (deg < 64)
+        let x = Fq4 { c: [3, 1, 4, 1] };
+        let a = Poly::monomial(40, 5, &q);
+        let b = Poly::monomial(20, 7, &q);
+        assert_eq!(e.mul(&a.eval_ext(&x, &e), &b.eval_ext(&x, &e)), a.mul(&b, &q).eval_ext(&x, &e));
+    }
+
     #[test]
     fn sigma_inv_is_involution() {
         let q = ModQ::new(229);
@@ -156,4 +566,22 @@ mod tests {
         // constant term preserved
         assert_eq!(a.ct(), b.ct());
     }
+
+    #[test]
+    fn wire_poly_roundtrip() {
+        let q = ModQ::new(229);
+        let mut p = Poly::zero();
+        for i in 0..D { p.c[i] = (i as u32 * 37) % q.q; }
+        let bytes = p.to_bytes();
+        assert_eq!(Poly::from_bytes(&bytes, &q), Ok(p));
+        assert_eq!(Poly::from_bytes(&bytes[..bytes.len() - 1], &q), Err(DecodeError::Truncated));
+        let mut long = bytes.clone();
+        long.push(0);
+        assert_eq!(Poly::from_bytes(&long, &q), Err(DecodeError::Trailing(1)));
+        let mut bad = bytes.clone();
+        bad[5..9].copy_from_slice(&229u32.to_le_bytes());
+        assert_eq!(Poly::from_bytes(&bad, &q), Err(DecodeError::Unreduced { value: 229, q: 229 }));
+        bad[4] = 9;
+        assert_eq!(Poly::from_bytes(&bad, &q), Err(DecodeError::Version(9)));
+    }
 }
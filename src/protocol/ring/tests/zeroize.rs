@@ -0,0 +1,53 @@
+//! Wipe-on-free check for `SecretPolyVec`. It installs a global allocator, so it
+//! lives in its own test binary rather than in the library's unit tests.
+#![cfg(feature = "zeroize")]
+
+use greyhound_ring::{ModQ, Poly, SecretPolyVec, D};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Frees of this (otherwise unused) size are inspected before being released.
+const PROBE_POLYS: usize = 37;
+static PROBE_FREES: AtomicUsize = AtomicUsize::new(0);
+static PROBE_DIRTY: AtomicUsize = AtomicUsize::new(0);
+
+struct InspectOnFree;
+unsafe impl GlobalAlloc for InspectOnFree {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 { System.alloc(layout) }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() == PROBE_POLYS * std::mem::size_of::<Poly>() {
+            let bytes = std::slice::from_raw_parts(ptr, layout.size());
+            PROBE_FREES.fetch_add(1, Ordering::SeqCst);
+            if bytes.iter().any(|&b| b != 0) { PROBE_DIRTY.fetch_add(1, Ordering::SeqCst); }
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: InspectOnFree = InspectOnFree;
+
+#[test]
+fn secret_vec_is_wiped_before_free() {
+    let q = ModQ::new(229);
+    let v: Vec<Poly> = (0..PROBE_POLYS).map(|i| Poly::monomial(i % D, 1 + i as u32, &q)).collect();
+    assert_eq!(v.capacity(), PROBE_POLYS);
+    drop(SecretPolyVec::new(v));
+    assert!(PROBE_FREES.load(Ordering::SeqCst) >= 1);
+    assert_eq!(PROBE_DIRTY.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn append_to_wipes_the_outgrown_buffer() {
+    let q = ModQ::new(229);
+    let mut dst: Vec<Poly> = (0..PROBE_POLYS).map(|i| Poly::monomial(i % D, 2 + i as u32, &q)).collect();
+    assert_eq!(dst.capacity(), PROBE_POLYS);
+    let frees = PROBE_FREES.load(Ordering::SeqCst);
+    // dst is full, so it has to grow: its old buffer is freed, wiped
+    SecretPolyVec::append_to(&mut dst, vec![Poly::monomial(0, 1, &q)]);
+    assert_eq!(dst.len(), PROBE_POLYS + 1);
+    assert_eq!(dst[PROBE_POLYS], Poly::monomial(0, 1, &q));
+    assert!(PROBE_FREES.load(Ordering::SeqCst) > frees);
+    assert_eq!(PROBE_DIRTY.load(Ordering::SeqCst), 0);
+    drop(SecretPolyVec::new(dst));
+}
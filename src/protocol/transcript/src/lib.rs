@@ -4,6 +4,7 @@
 use sha3::{Shake128, digest::{Update, ExtendableOutput, XofReader}};
 use greyhound_ring::{ModQ, Poly, D};
 
+#[derive(Clone)]
 pub struct Fs {
     st: Shake128
 }
@@ -34,6 +35,13 @@ impl Fs {
         Box::new(st.finalize_xof())
     }
 
+    /// 32-byte digest of everything absorbed so far (e.g. a parameter fingerprint).
+    pub fn digest(&self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        self.reader().read(&mut out);
+        out
+    }
+
 
     /// Draw a single ring challenge with τ1 entries in {±1} and τ2 entries in {±2}.
     fn sample_challenge_poly(reader: &mut dyn XofReader, q: &ModQ, tau1: usize, tau2: usize) -> Poly {
@@ -82,6 +90,23 @@ impl Fs {
             .collect()
     }
 
+    /// `len` entries in {-1, 0, 1} with Pr[0] = 1/2, Pr[±1] = 1/4 (JL projections).
+    pub fn ternary(&self, len: usize) -> Vec<i8> {
+        let mut rdr = self.reader();
+        let mut out = Vec::with_capacity(len);
+        let mut buf = [0u8; 64];
+        while out.len() < len {
+            rdr.read(&mut buf);
+            for byte in buf {
+                for k in 0..4 {
+                    if out.len() == len { break; }
+                    out.push(match (byte >> (2 * k)) & 3 { 2 => 1, 3 => -1, _ => 0 });
+                }
+            }
+        }
+        out
+    }
+
     pub fn alphas(&self, L: usize, q: &ModQ) -> Vec<u32> {
         let mut rdr = self.reader();
         let mut out = Vec::with_capacity(L);
@@ -0,0 +1,313 @@
+// Ring switching R_q -> Fq4 through the trace to a subfield, without quotients.
+//
+// H = <σ_{-1}, σ_17> ⊂ Gal(R_q/Z_q) (σ_k: X -> X^k) has order 16, so the fixed
+// subring S = R_q^H has Z_q-dimension 64/16 = 4. With q ≡ 5 mod 8, X^64+1 has two
+// irreducible factors of degree 32 that σ_{-1} swaps, and S is the degree-4
+// subfield of F_{q^32}: S ≅ F_{q^4}. `Subring` fixes an explicit isomorphism
+// φ: S -> Fq4 by sending a generator θ of S to a root ρ ∈ Fq4 of its minimal
+// polynomial.
+//
+// Tr_H(a) = Σ_{σ∈H} σ(a) is S-linear and the form (a, b) -> Tr_H(a·b) is
+// nondegenerate, so for e ≠ 0 and a uniform challenge c ∈ R_q, Tr_H(c·e) is
+// uniform on S. A row Σ_k M_ik z_k = y_i of R_q therefore switches to
+//     Σ_k Σ_d z_k[d]·φ(Tr_H(c·M_ik·X^d)) = φ(Tr_H(c·y_i)),
+// which a wrong y_i passes with probability q^-4. Every product is taken mod
+// X^64+1, so no quotient r joins the witness: w = z only.
+
+use crate::field::{Fq, Fq4, fq2fq4};
+use crate::{N, Q};
+use ark_ff::{Field, UniformRand};
+use ark_std::{Zero, One};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+// Product in F[X]/(X^64+1).
+pub fn negacyclic_mul<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut c = vec![F::zero(); N];
+    for i in 0..N {
+        if a[i].is_zero() { continue; }
+        for j in 0..N {
+            let prod = a[i] * b[j];
+            if i + j < N { c[i + j] += prod; } else { c[i + j - N] -= prod; }
+        }
+    }
+    c
+}
+
+// σ_k: X -> X^k on R_q, k odd (X^64 = -1).
+pub fn automorphism(a: &[Fq], k: usize) -> Vec<Fq> {
+    let mut b = vec![Fq::zero(); N];
+    for (j, &c) in a.iter().enumerate() {
+        let e = j * k % (2 * N);
+        if e < N { b[e] += c; } else { b[e - N] -= c; }
+    }
+    b
+}
+
+// The exponents k of H = <σ_{-1}, σ_17>: ±17^i mod 128, i < 8.
+fn subgroup_h() -> Vec<usize> {
+    let mut h = Vec::with_capacity(16);
+    let mut g = 1;
+    for _ in 0..8 {
+        h.push(g);
+        h.push(2 * N - g);
+        g = g * 17 % (2 * N);
+    }
+    h
+}
+
+// Tr_H(a) = Σ_{σ∈H} σ(a), an element of S.
+pub fn trace_h(a: &[Fq]) -> Vec<Fq> {
+    let mut t = vec![Fq::zero(); N];
+    for k in subgroup_h() {
+        for (ti, ai) in t.iter_mut().zip(automorphism(a, k)) { *ti += ai; }
+    }
+    t
+}
+
+fn lift(a: &[Fq]) -> Vec<Fq4> {
+    a.iter().map(|&x| fq2fq4(x)).collect()
+}
+
+fn monomial(e: usize) -> Vec<Fq> {
+    let mut x = vec![Fq::zero(); N];
+    x[e] = Fq::one();
+    x
+}
+
+// The unique x with Σ_j x_j·cols[j] = rhs, or None if the cols are dependent or
+// rhs is outside their span.
+fn solve(cols: &[Vec<Fq>], rhs: &[Fq]) -> Option<Vec<Fq>> {
+    let n = cols.len();
+    let mut rows: Vec<Vec<Fq>> = (0..rhs.len())
+        .map(|t| cols.iter().map(|c| c[t]).chain([rhs[t]]).collect())
+        .collect();
+    for j in 0..n {
+        let p = (j..rows.len()).find(|&i| !rows[i][j].is_zero())?;
+        rows.swap(j, p);
+        let inv = rows[j][j].inverse()?;
+        for x in rows[j].iter_mut() { *x *= inv; }
+        let pivot = rows[j].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            let f = row[j];
+            if i == j || f.is_zero() { continue; }
+            for (x, &p) in row.iter_mut().zip(&pivot) { *x -= f * p; }
+        }
+    }
+    if rows[n..].iter().any(|row| !row[n].is_zero()) { return None; }
+    Some(rows[..n].iter().map(|row| row[n]).collect())
+}
+
+// ---- polynomials over Fq4 (low to high, no trailing zeros), for the root of θ's minimal polynomial ----
+
+fn trim(mut a: Vec<Fq4>) -> Vec<Fq4> {
+    while a.last().is_some_and(|c| c.is_zero()) { a.pop(); }
+    a
+}
+
+fn poly_rem(a: &[Fq4], m: &[Fq4]) -> Vec<Fq4> {
+    let mut r = trim(a.to_vec());
+    let lead_inv = m[m.len() - 1].inverse().expect("m is trimmed");
+    while r.len() >= m.len() {
+        let f = r[r.len() - 1] * lead_inv;
+        let shift = r.len() - m.len();
+        for (j, &mj) in m.iter().enumerate() { r[shift + j] -= f * mj; }
+        r = trim(r);
+    }
+    r
+}
+
+fn poly_mulmod(a: &[Fq4], b: &[Fq4], m: &[Fq4]) -> Vec<Fq4> {
+    if a.is_empty() || b.is_empty() { return Vec::new(); }
+    let mut c = vec![Fq4::zero(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() { c[i + j] += x * y; }
+    }
+    poly_rem(&c, m)
+}
+
+fn poly_powmod(base: &[Fq4], mut e: u128, m: &[Fq4]) -> Vec<Fq4> {
+    let mut acc = vec![Fq4::one()];
+    let mut b = poly_rem(base, m);
+    while e > 0 {
+        if e & 1 == 1 { acc = poly_mulmod(&acc, &b, m); }
+        b = poly_mulmod(&b, &b, m);
+        e >>= 1;
+    }
+    acc
+}
+
+// Monic gcd; a must be nonzero.
+fn poly_gcd(a: &[Fq4], b: &[Fq4]) -> Vec<Fq4> {
+    let (mut a, mut b) = (trim(a.to_vec()), trim(b.to_vec()));
+    while !b.is_empty() {
+        let r = poly_rem(&a, &b);
+        a = std::mem::replace(&mut b, r);
+    }
+    let inv = a[a.len() - 1].inverse().expect("a is nonzero");
+    a.iter().map(|&c| c * inv).collect()
+}
+
+// A root in Fq4 of a monic m with distinct roots, all in Fq4 (Cantor–Zassenhaus:
+// gcd(m, (T + δ)^((q^4-1)/2) - 1) splits off the roots ρ with ρ + δ a square).
+// δ is drawn from a fixed seed, so prover and verifier agree on the root.
+fn find_root(m: &[Fq4]) -> Fq4 {
+    let half = ((Q as u128).pow(4) - 1) / 2;
+    let mut rng = StdRng::seed_from_u64(0x5EED);
+    let mut f = m.to_vec();
+    while f.len() > 2 {
+        let delta = Fq4::rand(&mut rng);
+        let mut h = poly_powmod(&[delta, Fq4::one()], half, &f);
+        if h.is_empty() { h.push(Fq4::zero()); }
+        h[0] -= Fq4::one();
+        let g = poly_gcd(&f, &h);
+        if g.len() > 1 && g.len() < f.len() { f = g; }
+    }
+    -f[0] / f[1]
+}
+
+/// S = R_q^H with the field isomorphism φ: S -> Fq4 given by θ -> ρ.
+pub struct Subring {
+    theta_pows: Vec<Vec<Fq>>, // 1, θ, θ², θ³: a Z_q basis of S
+    rho_pows: Vec<Fq4>,       // their images 1, ρ, ρ², ρ³
+    trace_x: Vec<Fq4>,        // φ(Tr_H(X^e)), e < 64
+}
+
+impl Default for Subring {
+    fn default() -> Self { Self::new() }
+}
+
+impl Subring {
+    pub fn new() -> Self {
+        // θ = Tr_H(X^j) for the first j whose powers 1, θ, θ², θ³ are independent;
+        // then θ^4 = Σ c_j θ^j gives its minimal polynomial T^4 - Σ c_j T^j.
+        let (theta_pows, c) = (1..N).find_map(|j| {
+            let theta = trace_h(&monomial(j));
+            let mut pows = vec![monomial(0)];
+            for _ in 0..4 { pows.push(negacyclic_mul(&pows[pows.len() - 1], &theta)); }
+            let c = solve(&pows[..4], &pows[4])?;
+            pows.truncate(4);
+            Some((pows, c))
+        }).expect("S = R_q^H is a field of degree 4");
+        let mut min_poly: Vec<Fq4> = c.iter().map(|&cj| -fq2fq4(cj)).collect();
+        min_poly.push(Fq4::one());
+        let rho = find_root(&min_poly);
+        let rho_pows = (0..4).scan(Fq4::one(), |p, _| { let cur = *p; *p *= rho; Some(cur) }).collect();
+
+        let mut sub = Subring { theta_pows, rho_pows, trace_x: Vec::new() };
+        sub.trace_x = (0..N)
+            .map(|e| sub.to_fq4(&trace_h(&monomial(e))).expect("Tr_H lands in S"))
+            .collect();
+        sub
+    }
+
+    /// φ(s), or None if s is not in S.
+    pub fn to_fq4(&self, s: &[Fq]) -> Option<Fq4> {
+        let c = solve(&self.theta_pows, s)?;
+        Some(c.iter().zip(&self.rho_pows).fold(Fq4::zero(), |acc, (&cj, &rj)| acc + fq2fq4(cj) * rj))
+    }
+
+    /// φ(Tr_H(a·X^d)), extended Fq4-linearly to a with Fq4 coefficients.
+    pub fn trace_shifted(&self, a: &[Fq4], d: usize) -> Fq4 {
+        a.iter().enumerate().fold(Fq4::zero(), |acc, (t, &at)| {
+            let e = t + d;
+            if e < N { acc + at * self.trace_x[e] } else { acc - at * self.trace_x[e - N] }
+        })
+    }
+}
+
+// Φ_k[d] = φ(Tr_H(c·G_k·X^d)), G_k = Σ_i eq(τ,i)·M_ik: one Fq4 coefficient row per
+// witness row k. eq_tau has one entry per constraint row i (the eq table at τ).
+pub fn switch_rows(sub: &Subring, m: &[Vec<Vec<Fq>>], eq_tau: &[Fq4], c: &[Fq]) -> Vec<Vec<Fq4>> {
+    let cols = m[0].len();
+    let c = lift(c);
+    let mut out = Vec::with_capacity(cols);
+    for k in 0..cols {
+        let mut g = vec![Fq4::zero(); N];
+        for (i, row) in m.iter().enumerate() {
+            for d in 0..N {
+                g[d] += eq_tau[i] * fq2fq4(row[k][d]);
+            }
+        }
+        let g = negacyclic_mul(&g, &c);
+        out.push((0..N).map(|d| sub.trace_shifted(&g, d)).collect());
+    }
+    out
+}
+
+// Σ_i eq(τ,i)·φ(Tr_H(c·y_i)): what the switched table sums to when M z = y.
+pub fn switched_claim(sub: &Subring, y: &[Vec<Fq>], eq_tau: &[Fq4], c: &[Fq]) -> Fq4 {
+    y.iter().zip(eq_tau).fold(Fq4::zero(), |acc, (yi, &e)| acc + e * sub.trace_shifted(&lift(&negacyclic_mul(c, yi)), 0))
+}
+
+// f[k + (d << mk)] = w[k][d]·Φ_k[d], summing to `switched_claim`; input to
+// sumcheck_prove_from_table.
+pub fn build_f_table_switched(w_table: &[Fq], phi: &[Vec<Fq4>], mk: usize, md: usize) -> Vec<Fq4> {
+    let rows_k = 1usize << mk;
+    let cols_d = 1usize << md;
+    let mut out = vec![Fq4::zero(); rows_k * cols_d];
+
+    for d in 0..cols_d {
+        for k in 0..phi.len() {
+            let idx = k + (d << mk);
+            out[idx] = fq2fq4(w_table[idx]) * phi[k][d];
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mle::mle_from_table;
+    use crate::smcheck::{sumcheck_prove_from_table, sumcheck_verify_from_table, SumcheckError};
+    use rand::Rng;
+
+    fn random_ring(rng: &mut StdRng) -> Vec<Fq> {
+        (0..N).map(|_| Fq::rand(rng)).collect()
+    }
+
+    #[test]
+    fn subring_is_a_field_isomorphic_to_fq4() {
+        let sub = Subring::new();
+        let mut rng = StdRng::seed_from_u64(1);
+        let (a, b) = (trace_h(&random_ring(&mut rng)), trace_h(&random_ring(&mut rng)));
+        // Tr_H(a) is fixed by H
+        for k in subgroup_h() { assert_eq!(automorphism(&a, k), a); }
+        let (fa, fb) = (sub.to_fq4(&a).unwrap(), sub.to_fq4(&b).unwrap());
+        let sum: Vec<Fq> = a.iter().zip(&b).map(|(x, y)| *x + y).collect();
+        assert_eq!(sub.to_fq4(&sum), Some(fa + fb));
+        assert_eq!(sub.to_fq4(&negacyclic_mul(&a, &b)), Some(fa * fb));
+        // X is not in S
+        assert_eq!(sub.to_fq4(&monomial(1)), None);
+    }
+
+    #[test]
+    fn switched_rows_sum_to_the_claim_without_quotients() {
+        let sub = Subring::new();
+        let mut rng = StdRng::seed_from_u64(2);
+        let (rows, cols, mk) = (4, 4, 2);
+        let m: Vec<Vec<Vec<Fq>>> = (0..rows).map(|_| (0..cols).map(|_| random_ring(&mut rng)).collect()).collect();
+        let z: Vec<Vec<Fq>> = (0..cols).map(|_| (0..N).map(|_| Fq::from(rng.gen_range(0u64..3)) - Fq::one()).collect()).collect();
+        let mut y: Vec<Vec<Fq>> = m.iter().map(|row| {
+            row.iter().zip(&z).fold(vec![Fq::zero(); N], |acc, (mik, zk)| {
+                acc.iter().zip(negacyclic_mul(mik, zk)).map(|(a, b)| *a + b).collect()
+            })
+        }).collect();
+        let eq_tau: Vec<Fq4> = (0..rows).map(|_| Fq4::rand(&mut rng)).collect();
+        let c = random_ring(&mut rng);
+
+        let phi = switch_rows(&sub, &m, &eq_tau, &c);
+        let w = mle_from_table(&z).evaluations;
+        let table = build_f_table_switched(&w, &phi, mk, 6);
+        let claim = switched_claim(&sub, &y, &eq_tau, &c);
+        let rs: Vec<Fq4> = (0..mk + 6).map(|_| Fq4::rand(&mut rng)).collect();
+        let proof = sumcheck_prove_from_table(table.clone(), &rs);
+        assert_eq!(sumcheck_verify_from_table(table.clone(), claim, &proof, &rs), Ok(()));
+
+        // a wrong y_0 changes the claim
+        y[0][5] += Fq::one();
+        let bad = switched_claim(&sub, &y, &eq_tau, &c);
+        assert_eq!(sumcheck_verify_from_table(table, bad, &proof, &rs), Err(SumcheckError::InitSum));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::field::{Fq, Fq4, fq2fq4};
+use ark_ff::Field;
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension as _};
 use ark_std::{Zero, One};
 
@@ -112,6 +113,66 @@ pub fn sumcheck_prove_from_table_range(
     SumcheckProof { g_coeffs: coeffs, final_eval: layer[0] }
 }
 
+/// Why a sumcheck transcript was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SumcheckError {
+    /// The table has `got` entries instead of 2^rounds = `expected`.
+    TableSize { expected: usize, got: usize },
+    /// The proof has `got` round messages for `expected` challenges.
+    Rounds { expected: usize, got: usize },
+    /// The table does not sum to the claim.
+    InitSum,
+    /// Round `round`: the table sum differs from g(0) + g(1).
+    RoundSum { round: usize },
+    /// Round `round`: g(r) differs from the sum of the folded table.
+    RoundEval { round: usize },
+    /// The last folded entry differs from the claimed final evaluation.
+    FinalEval,
+}
+
+impl std::fmt::Display for SumcheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SumcheckError::TableSize { expected, got } => write!(f, "table has {} entries, expected {}", got, expected),
+            SumcheckError::Rounds { expected, got } => write!(f, "expected {} rounds, got {}", expected, got),
+            SumcheckError::InitSum => write!(f, "init sum mismatch"),
+            SumcheckError::RoundSum { round } => write!(f, "round {} sum mismatch", round),
+            SumcheckError::RoundEval { round } => write!(f, "round {} eval mismatch", round),
+            SumcheckError::FinalEval => write!(f, "final eval mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for SumcheckError {}
+
+// Verifier side of sumcheck_prove_from_table(_range): replays the folding of
+// `layer` against the round messages g_t = c0 + c1·X.
+pub fn sumcheck_verify_from_table<F: Field>(
+    mut layer: Vec<F>,
+    claim: F,
+    proof: &SumcheckProof<F>,
+    rs: &[F],
+) -> Result<(), SumcheckError> {
+    if proof.g_coeffs.len() != rs.len() {
+        return Err(SumcheckError::Rounds { expected: rs.len(), got: proof.g_coeffs.len() });
+    }
+    if layer.len() != 1 << rs.len() {
+        return Err(SumcheckError::TableSize { expected: 1 << rs.len(), got: layer.len() });
+    }
+    let mut sum = layer.iter().fold(F::zero(), |acc, &x| acc + x);
+    if sum != claim { return Err(SumcheckError::InitSum); }
+
+    for (round, (&(c0, c1), &r)) in proof.g_coeffs.iter().zip(rs).enumerate() {
+        // g_t(0) + g_t(1) = (c0) + (c0+c1) = 2*c0 + c1
+        if sum != c0 + (c0 + c1) { return Err(SumcheckError::RoundSum { round }); }
+        layer = layer.chunks(2).map(|ab| ab[0] * (F::one() - r) + ab[1] * r).collect();
+        sum = layer.iter().fold(F::zero(), |acc, &x| acc + x);
+        if c0 + c1 * r != sum { return Err(SumcheckError::RoundEval { round }); }
+    }
+    if layer[0] != proof.final_eval { return Err(SumcheckError::FinalEval); }
+    Ok(())
+}
+
 // ---------------------------------------
 
 fn eval_poly_low_to_high_at_alpha_fq4(coeffs_low_to_high: &[Fq], alpha: Fq4) -> Fq4 {
@@ -150,4 +211,32 @@ pub fn compute_a_eq_sum_i_prime_fq4(
         a += wj * t_alpha[j];
     }
     a
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn verifier_names_the_failing_check() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let table: Vec<Fq4> = (0..16).map(|_| Fq4::rand(&mut rng)).collect();
+        let claim = table.iter().fold(Fq4::zero(), |acc, x| acc + x);
+        let rs: Vec<Fq4> = (0..4).map(|_| Fq4::rand(&mut rng)).collect();
+        let proof = sumcheck_prove_from_table(table.clone(), &rs);
+        assert_eq!(sumcheck_verify_from_table(table.clone(), claim, &proof, &rs), Ok(()));
+
+        assert_eq!(sumcheck_verify_from_table(table.clone(), claim + Fq4::one(), &proof, &rs), Err(SumcheckError::InitSum));
+        assert_eq!(sumcheck_verify_from_table(table[..8].to_vec(), claim, &proof, &rs),
+                   Err(SumcheckError::TableSize { expected: 16, got: 8 }));
+        assert_eq!(sumcheck_verify_from_table(table.clone(), claim, &proof, &rs[..3]),
+                   Err(SumcheckError::Rounds { expected: 3, got: 4 }));
+        let mut bad = SumcheckProof { g_coeffs: proof.g_coeffs.clone(), final_eval: proof.final_eval };
+        bad.g_coeffs[2].0 += Fq4::one();
+        assert_eq!(sumcheck_verify_from_table(table.clone(), claim, &bad, &rs), Err(SumcheckError::RoundSum { round: 2 }));
+        let bad = SumcheckProof { g_coeffs: proof.g_coeffs.clone(), final_eval: proof.final_eval + Fq4::one() };
+        assert_eq!(sumcheck_verify_from_table(table, claim, &bad, &rs), Err(SumcheckError::FinalEval));
+    }
+}